@@ -0,0 +1,165 @@
+//! Measures the throughput of the concurrent `WordGetMany` requests sent to a server.
+//!
+//! Run it with the same environment variables as `examples/io_remote.rs`,
+//! where `ipiis_client_account_primary_address` points to the server (e.g. `127.0.0.1:9801`):
+//! `cargo bench -p ipdis-api --bench word_get_many`
+//!
+//! To compare with the synchronous baseline, which runs the queries on the executor,
+//! run it again with `--features inline-blocking`.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::{GetWords, GetWordsParent, Ipdis, KIND},
+    server::IpdisServer,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis, server::IpiisServer};
+use ipis::{
+    core::value::{hash::Hash, text::Text},
+    env::{infer, Infer},
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+const DURATION: Duration = Duration::from_secs(5);
+const NUM_WORDS: u32 = 100;
+const NUM_TASKS: &[usize] = &[1, 8, 64];
+
+#[tokio::main]
+async fn main() {
+    // deploy a server
+    let server = {
+        ::std::env::set_var("ipiis_router_db", "/tmp/ipdis-bench-server-ipiis-router-db");
+        IpdisServer::genesis(9801).await.unwrap()
+    };
+    let server_account = {
+        let server: &IpiisServer = server.as_ref();
+        let account = unsafe { server.account_me().unwrap() };
+
+        // register the environment variables
+        ::std::env::set_var("ipis_account_me", account.to_string());
+
+        account.account_ref()
+    };
+    tokio::spawn(async move { server.run().await });
+
+    // create a guarantor client, which shares the storage with the server
+    let client_guarantor = {
+        ::std::env::set_var(
+            "ipiis_router_db",
+            "/tmp/ipdis-bench-client-guarantor-ipiis-router-db",
+        );
+        IpdisClient::infer().await
+    };
+
+    // create a client
+    let client = {
+        ::std::env::set_var(
+            "ipiis_router_db",
+            "/tmp/ipdis-bench-client-guarantee-ipiis-router-db",
+        );
+        IpiisClient::genesis(None).await.unwrap()
+    };
+    let client_account = *client.account_ref();
+    client
+        .set_account_primary(KIND.as_ref(), &server_account)
+        .await
+        .unwrap();
+    client
+        .set_address(
+            KIND.as_ref(),
+            &server_account,
+            &infer("ipiis_client_account_primary_address").unwrap(),
+        )
+        .await
+        .unwrap();
+
+    // register the client as guarantee
+    let guarantee = client_guarantor
+        .as_ref()
+        .sign_as_guarantor(client.sign_owned(server_account, client_account).unwrap())
+        .unwrap();
+    client_guarantor
+        .add_guarantee_unchecked(&guarantee)
+        .await
+        .unwrap();
+
+    // create a sample word to be stored
+    let namespace = "ipdis-api-bench";
+    let kind = "ipdis-api-bench";
+    let parent = Hash::with_str("");
+    let word: WordHash = Word {
+        key: WordKey {
+            namespace: namespace.to_string(),
+            text: Text::with_en_us("hello world"),
+        },
+        kind: kind.to_string(),
+        relpath: true,
+        path: Path {
+            value: "bafkreihm64a7oj6z4llxysvetldpxpgjs4tyvsqbbpo65olbyegpktkdli"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into();
+
+    // cleanup bench data
+    client_guarantor
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the words to be read
+    for _ in 0..NUM_WORDS {
+        let word = client.sign_owned(server_account, word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        cursor: None,
+        start_index: 0,
+        end_index: NUM_WORDS,
+    };
+
+    let client = Arc::new(client);
+    for &num_tasks in NUM_TASKS {
+        let started = Instant::now();
+        let tasks: Vec<_> = (0..num_tasks)
+            .map(|_| {
+                let client = client.clone();
+                tokio::spawn(async move {
+                    let mut count = 0u64;
+                    while started.elapsed() < DURATION {
+                        client.get_word_many_unchecked(None, &query).await.unwrap();
+                        count += 1;
+                    }
+                    count
+                })
+            })
+            .collect();
+
+        let mut count = 0;
+        for task in tasks {
+            count += task.await.unwrap();
+        }
+        let elapsed = started.elapsed().as_secs_f64();
+        println!(
+            "word_get_many/{num_tasks} tasks: {:.1} requests/s",
+            count as f64 / elapsed,
+        );
+    }
+
+    // cleanup bench data
+    client_guarantor
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+}
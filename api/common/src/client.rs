@@ -0,0 +1,603 @@
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWords, GetWordsCounts, GetWordsCountsOutput, GuaranteeRole,
+    GuaranteeScope, Health, Ipdis, IpdisError, NamespaceStats, Page, PutWordsError, Quota,
+    SignedCursor,
+};
+use ipiis_api::common::Ipiis;
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned, Verifier},
+        anyhow::{bail, Result},
+        chrono::Utc,
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
+    env::Infer,
+    path::{DynPath, Path},
+    word::WordHash,
+};
+
+use crate::{
+    drain::Drain,
+    metrics::Metrics,
+    nonce,
+    policy::RequestPolicy,
+    quota::{QuotaPolicy, RateLimiter},
+    storage::IpdisStorage,
+};
+
+pub struct IpdisClientInner<IpiisClient, Storage> {
+    pub ipiis: IpiisClient,
+    storage: Storage,
+    policy: RequestPolicy,
+    quota: QuotaPolicy,
+    rate_limiter: RateLimiter,
+    metrics: Metrics,
+    drain: Drain,
+}
+
+impl<IpiisClient, Storage> AsRef<::ipiis_api::client::IpiisClient>
+    for IpdisClientInner<IpiisClient, Storage>
+where
+    IpiisClient: AsRef<::ipiis_api::client::IpiisClient>,
+{
+    fn as_ref(&self) -> &::ipiis_api::client::IpiisClient {
+        self.ipiis.as_ref()
+    }
+}
+
+impl<IpiisClient, Storage> AsRef<::ipiis_api::server::IpiisServer>
+    for IpdisClientInner<IpiisClient, Storage>
+where
+    IpiisClient: AsRef<::ipiis_api::server::IpiisServer>,
+{
+    fn as_ref(&self) -> &::ipiis_api::server::IpiisServer {
+        self.ipiis.as_ref()
+    }
+}
+
+#[async_trait]
+impl<'a, IpiisClient, Storage> Infer<'a> for IpdisClientInner<IpiisClient, Storage>
+where
+    Self: Send,
+    IpiisClient: Infer<'a, GenesisResult = IpiisClient> + Send,
+    <IpiisClient as Infer<'a>>::GenesisArgs: Sized,
+    Storage: IpdisStorage,
+{
+    type GenesisArgs = <IpiisClient as Infer<'a>>::GenesisArgs;
+    type GenesisResult = Self;
+
+    async fn try_infer() -> Result<Self>
+    where
+        Self: Sized,
+    {
+        let ipiis = IpiisClient::try_infer().await?;
+        Self::with_ipiis_client(ipiis).await
+    }
+
+    async fn genesis(
+        args: <Self as Infer<'a>>::GenesisArgs,
+    ) -> Result<<Self as Infer<'a>>::GenesisResult> {
+        let ipiis = IpiisClient::genesis(args).await?;
+        Self::with_ipiis_client(ipiis).await
+    }
+}
+
+impl<IpiisClient, Storage> IpdisClientInner<IpiisClient, Storage>
+where
+    Storage: IpdisStorage,
+{
+    pub async fn with_ipiis_client(ipiis: IpiisClient) -> Result<Self> {
+        Ok(Self::with_storage(ipiis, Storage::try_infer().await?)
+            .with_policy(RequestPolicy::try_infer()?)
+            .with_quota(QuotaPolicy::try_infer()?))
+    }
+
+    /// Ensures that the signed request has not been handled before.
+    pub async fn ensure_fresh(&self, request: &GuaranteeSigned) -> Result<()> {
+        self.policy.ensure_alive(request)?;
+        self.ensure_nonce(request).await
+    }
+
+    async fn ensure_nonce(&self, request: &GuaranteeSigned) -> Result<()> {
+        // the nonce is kept until the policy rejects the request anyway
+        let retention_date = self.policy.retention_date(request);
+        if self.storage.put_nonce(request, retention_date).await? {
+            Ok(())
+        } else {
+            bail!(nonce::replayed())
+        }
+    }
+
+    /// Ensures that the signatures of the record are valid.
+    pub fn ensure_verified<T>(&self, record: &T) -> Result<()>
+    where
+        T: Verifier,
+    {
+        record.verify(None).map_err(|e| {
+            self.metrics.add_auth_failure();
+            IpdisError::Unauthenticated(format!("invalid signature: {e}")).into()
+        })
+    }
+
+    /// Verifies the signatures of the records read back from the storage.
+    fn verify_records<'a, T>(&self, records: impl IntoIterator<Item = &'a T>) -> Result<()>
+    where
+        T: 'a + Verifier,
+    {
+        for record in records {
+            record
+                .verify(None)
+                .map_err(|e| IpdisError::Backend(format!("corrupted record: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+impl<IpiisClient, Storage> IpdisClientInner<IpiisClient, Storage>
+where
+    IpiisClient: Ipiis,
+    Storage: IpdisStorage,
+{
+    /// Returns the latest scope of the guarantee, given by the guarantor.
+    pub async fn get_scope(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<GuaranteeScope> {
+        match self
+            .storage
+            .get_guarantee_scope_latest(guarantee, guarantor)
+            .await?
+        {
+            Some(scope) => {
+                self.verify_records([&scope])?;
+                Ok(scope.data)
+            }
+            None => Ok(GuaranteeScope::with_account(*guarantee)),
+        }
+    }
+
+    /// Ensures that the granter does not give a wider scope than its own.
+    pub async fn ensure_grantable(
+        &self,
+        granter: &AccountRef,
+        scope: &GuaranteeScope,
+    ) -> Result<()> {
+        // the guarantor can give any scope
+        let guarantor = self.ipiis.account_ref();
+        if granter == guarantor {
+            return Ok(());
+        }
+
+        self.get_scope(granter, guarantor)
+            .await?
+            .ensure_covers(scope)
+            .map_err(|e| {
+                self.metrics.add_auth_failure();
+                e
+            })
+    }
+
+    /// Ensures that the cursor has been given by the server itself, and is still alive.
+    fn ensure_cursor(&self, cursor: Option<&SignedCursor>) -> Result<()> {
+        if let Some(cursor) = cursor {
+            if &cursor.metadata.guarantee.account != self.ipiis.account_ref() {
+                bail!(IpdisError::MalformedQuery(
+                    "malformed cursor: the cursor is given by another server".into(),
+                ))
+            }
+            self.ensure_verified(cursor)?;
+            self.policy.ensure_alive(&cursor.metadata)?;
+        }
+        Ok(())
+    }
+
+    /// Signs the cursor of the page, so that the clients cannot forge it.
+    fn sign_cursor<T>(&self, page: Page<T>) -> Result<Page<T, SignedCursor>> {
+        let guarantor = self.ipiis.account_ref();
+        page.try_map_cursor(|cursor| self.ipiis.sign_owned(*guarantor, cursor))
+    }
+}
+
+impl<IpiisClient, Storage> IpdisClientInner<IpiisClient, Storage> {
+    pub fn with_storage(ipiis: IpiisClient, storage: Storage) -> Self {
+        Self {
+            ipiis,
+            storage,
+            policy: Default::default(),
+            quota: Default::default(),
+            rate_limiter: Default::default(),
+            metrics: Default::default(),
+            drain: Default::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RequestPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn policy(&self) -> &RequestPolicy {
+        &self.policy
+    }
+
+    pub fn with_quota(mut self, quota: QuotaPolicy) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    pub fn quota(&self) -> &QuotaPolicy {
+        &self.quota
+    }
+
+    /// Ensures that the guarantee has not sent too many requests.
+    pub fn ensure_rate(&self, guarantee: &AccountRef, call: &'static str) -> Result<()> {
+        match &self.quota.rate {
+            Some(rate) => self.rate_limiter.acquire(rate, guarantee, call),
+            None => Ok(()),
+        }
+    }
+
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn drain(&self) -> &Drain {
+        &self.drain
+    }
+
+    pub fn storage(&self) -> &Storage {
+        &self.storage
+    }
+}
+
+#[async_trait]
+impl<IpiisClient, Storage> Ipdis for IpdisClientInner<IpiisClient, Storage>
+where
+    IpiisClient: Ipiis + Send + Sync,
+    Storage: IpdisStorage,
+{
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        role: GuaranteeRole,
+        namespace: Option<&Hash>,
+    ) -> Result<()> {
+        let guarantor_now = self.ipiis.account_ref();
+        if guarantor != guarantor_now {
+            self.metrics.add_auth_failure();
+            bail!(IpdisError::Unauthenticated(
+                "failed to authenticate the guarantor".into(),
+            ))
+        }
+
+        // skip authentication for self-authentication
+        if guarantee == guarantor {
+            return Ok(());
+        }
+
+        if !self
+            .storage
+            .contains_guarantee(guarantee, guarantor)
+            .await?
+        {
+            self.metrics.add_auth_failure();
+            bail!(IpdisError::Unauthenticated(
+                "failed to authenticate the guarantee".into(),
+            ))
+        }
+
+        self.get_scope(guarantee, guarantor)
+            .await?
+            .ensure_allowed(role, namespace)
+            .map_err(|e| {
+                self.metrics.add_auth_failure();
+                e
+            })
+    }
+
+    async fn get_health(&self) -> Result<Health> {
+        let (storage_error, pending_migrations) = match self.storage.get_pending_migrations().await
+        {
+            Ok(pending_migrations) => (None, pending_migrations),
+            Err(e) => (Some(e.to_string()), 0),
+        };
+
+        Ok(Health {
+            account: *self.ipiis.account_ref(),
+            storage_error,
+            pending_migrations,
+        })
+    }
+
+    async fn add_guarantee_unchecked(
+        &self,
+        guarantee: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()> {
+        self.ensure_verified(guarantee)?;
+        self.policy.ensure_created(&guarantee.metadata.data)?;
+
+        self.storage.put_guarantee(guarantee).await
+    }
+
+    async fn get_guarantee_many_unchecked(
+        &self,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        if query.end_index <= query.start_index {
+            bail!(IpdisError::MalformedQuery(
+                "malformed index: end_index should be bigger than start_index".into(),
+            ))
+        }
+
+        let guarantor = self.ipiis.account_ref();
+
+        let guarantees = self.storage.get_guarantee_many(guarantor, query).await?;
+        self.verify_records(&guarantees)?;
+        Ok(guarantees)
+    }
+
+    async fn delete_guarantee_unchecked(&self, guarantee: &AccountRef) -> Result<()> {
+        self.storage.delete_guarantee_all(guarantee).await
+    }
+
+    async fn revoke_guarantee_unchecked(
+        &self,
+        revocation: &Data<GuaranteeSigned, AccountRef>,
+    ) -> Result<()> {
+        self.ensure_verified(revocation)?;
+        self.policy.ensure_created(&revocation.metadata.data)?;
+        let revocation = self.ipiis.sign_as_guarantor(*revocation)?;
+
+        self.storage.put_guarantee_revocation(&revocation).await
+    }
+
+    async fn set_guarantee_scope(
+        &self,
+        scope: &Data<GuaranteeSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        let granter = &scope.metadata.guarantee.account;
+        let guarantor = &scope.metadata.data.guarantor;
+        self.ensure_registered(granter, guarantor, GuaranteeRole::Admin, None)
+            .await?;
+        self.ensure_grantable(granter, &scope.data).await?;
+
+        self.set_guarantee_scope_unchecked(scope).await
+    }
+
+    async fn set_guarantee_scope_unchecked(
+        &self,
+        scope: &Data<GuaranteeSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        self.ensure_verified(scope)?;
+        self.policy.ensure_created(&scope.metadata.data)?;
+        let scope = self.ipiis.sign_as_guarantor(scope.clone())?;
+
+        self.storage.put_guarantee_scope(&scope).await
+    }
+
+    async fn get_dyn_path_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        let guarantor = self.ipiis.account_ref();
+        let guarantee = guarantee.unwrap_or(guarantor);
+
+        let path = self
+            .storage
+            .get_dyn_path(guarantee, guarantor, &(*path).remove_path())
+            .await?
+            .ok_or_else(|| IpdisError::NotFound("no such dynamic path".into()))?;
+        self.verify_records([&path])?;
+        Ok(path)
+    }
+
+    async fn get_dyn_path_at_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        let guarantor = self.ipiis.account_ref();
+        let guarantee = guarantee.unwrap_or(guarantor);
+
+        let path = self
+            .storage
+            .get_dyn_path_at(guarantee, guarantor, &(*path).remove_path(), timestamp)
+            .await?
+            .ok_or_else(|| IpdisError::NotFound(format!("no such dynamic path at {timestamp}")))?;
+        self.verify_records([&path])?;
+        Ok(path)
+    }
+
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>, SignedCursor>> {
+        if query.end_index <= query.start_index {
+            bail!(IpdisError::MalformedQuery(
+                "malformed index: end_index should be bigger than start_index".into(),
+            ))
+        }
+        self.ensure_cursor(query.cursor.as_ref())?;
+
+        let guarantor = self.ipiis.account_ref();
+        let guarantee = guarantee.unwrap_or(guarantor);
+
+        let page = self
+            .storage
+            .get_dyn_path_many(guarantee, guarantor, query)
+            .await?;
+        self.verify_records(&page.items)?;
+        self.sign_cursor(page)
+    }
+
+    async fn put_dyn_path_unchecked(
+        &self,
+        path: &Data<GuaranteeSigned, DynPath<Path>>,
+    ) -> Result<()> {
+        self.ensure_verified(path)?;
+        self.policy.ensure_created(&path.metadata.data)?;
+        let path = self.ipiis.sign_as_guarantor(*path)?;
+
+        self.storage
+            .put_dyn_path(&path, &self.quota, &self.policy)
+            .await
+    }
+
+    async fn delete_dyn_path_all_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        self.storage.delete_dyn_path_all(guarantee, namespace).await
+    }
+
+    async fn get_word_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>, SignedCursor>> {
+        if query.end_index <= query.start_index {
+            bail!(IpdisError::MalformedQuery(
+                "malformed index: end_index should be bigger than start_index".into(),
+            ))
+        }
+        self.ensure_cursor(query.cursor.as_ref())?;
+
+        let guarantor = self.ipiis.account_ref();
+        let guarantee = guarantee.unwrap_or(guarantor);
+
+        let page = self
+            .storage
+            .get_word_many(guarantee, guarantor, query)
+            .await?;
+        self.verify_records(&page.items)?;
+        self.sign_cursor(page)
+    }
+
+    async fn get_word_count_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput, SignedCursor>> {
+        if query.end_index <= query.start_index {
+            bail!(IpdisError::MalformedQuery(
+                "malformed index: end_index should be bigger than start_index".into(),
+            ))
+        }
+        self.ensure_cursor(query.cursor.as_ref())?;
+
+        let guarantor = self.ipiis.account_ref();
+        let guarantee = guarantee.unwrap_or(guarantor);
+
+        let page = self.storage.get_word_count_many(guarantee, query).await?;
+        self.sign_cursor(page)
+    }
+
+    async fn get_namespace_stats_unchecked(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        self.storage.get_namespace_stats(namespace).await
+    }
+
+    async fn get_quota_unchecked(&self, guarantee: &AccountRef) -> Result<Quota> {
+        let usage = self.storage.get_usage(guarantee).await?;
+        let rate = self.quota.rate.as_ref();
+
+        Ok(Quota {
+            rate_per_sec: rate.map(|rate| rate.per_sec),
+            rate_burst: rate.map(|rate| rate.burst),
+            rate_remaining: rate.map(|rate| self.rate_limiter.remaining(rate, guarantee)),
+            max_words: self.quota.max_words,
+            words: usage.words,
+            max_dyn_paths: self.quota.max_dyn_paths,
+            dyn_paths: usage.dyn_paths,
+        })
+    }
+
+    async fn put_word_unchecked(
+        &self,
+        parent: &Hash,
+        word: &Data<GuaranteeSigned, WordHash>,
+    ) -> Result<()> {
+        self.ensure_verified(word)?;
+        self.policy.ensure_created(&word.metadata.data)?;
+        let word = self.ipiis.sign_as_guarantor(*word)?;
+
+        self.storage
+            .put_word(parent, &word, &self.quota, &self.policy)
+            .await
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[(Hash, Data<GuaranteeSigned, WordHash>)],
+    ) -> Result<Vec<PutWordsError>> {
+        let guarantor = self.ipiis.account_ref();
+
+        let mut errors = vec![];
+        let mut indices = vec![];
+        let mut signed = vec![];
+        for (index, (parent, word)) in words.iter().enumerate() {
+            let error = if guarantee
+                .map(|guarantee| &word.metadata.guarantee.account != guarantee)
+                .unwrap_or_default()
+            {
+                IpdisError::Unauthenticated("Only the guarantee itself can put the word".into())
+            } else if &word.metadata.data.guarantor != guarantor {
+                IpdisError::Unauthenticated("Only the guarantor can sign the word".into())
+            } else if let Err(e) = self.ensure_verified(word) {
+                e.into()
+            } else if let Err(e) = self.policy.ensure_recent(&word.metadata) {
+                e.into()
+            } else {
+                match self.ipiis.sign_as_guarantor(*word) {
+                    Ok(word) => {
+                        indices.push(index);
+                        signed.push((*parent, word));
+                        continue;
+                    }
+                    Err(e) => e.into(),
+                }
+            };
+
+            errors.push(PutWordsError {
+                index: index.try_into()?,
+                error,
+            });
+        }
+
+        if !signed.is_empty() {
+            // map the rejected words back to the indices of the request
+            for mut error in self
+                .storage
+                .put_word_many(&signed, &self.quota, &self.policy)
+                .await?
+            {
+                error.index = indices[error.index as usize].try_into()?;
+                errors.push(error);
+            }
+            errors.sort_by_key(|error| error.index);
+        }
+        Ok(errors)
+    }
+
+    async fn delete_word_all_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        self.storage.delete_word_all(guarantee, namespace).await
+    }
+}
@@ -0,0 +1,64 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use ipdis_common::IpdisError;
+use ipis::{
+    core::anyhow::{bail, Result},
+    tokio::sync::Notify,
+};
+
+/// Tracks the in-flight requests, so that they can be drained before shutting down.
+#[derive(Default)]
+pub struct Drain {
+    closed: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl Drain {
+    /// Admits a new request, which is in flight until the guard is dropped.
+    pub fn enter(&self) -> Result<DrainGuard<'_>> {
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = DrainGuard { drain: self };
+
+        // the request is released by the guard
+        if self.closed.load(Ordering::SeqCst) {
+            bail!(IpdisError::Unavailable(
+                "shutting down: the server does not accept new requests".into(),
+            ))
+        }
+        Ok(guard)
+    }
+
+    /// Rejects the new requests from now on.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Waits until all the in-flight requests are completed.
+    pub async fn wait(&self) {
+        loop {
+            // register before checking, so that the last release is not missed
+            let idle = self.idle.notified();
+            if self.in_flight() == 0 {
+                break;
+            }
+            idle.await;
+        }
+    }
+}
+
+pub struct DrainGuard<'a> {
+    drain: &'a Drain,
+}
+
+impl<'a> Drop for DrainGuard<'a> {
+    fn drop(&mut self) {
+        if self.drain.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.drain.idle.notify_waiters();
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use core::{fmt, str::FromStr};
+
+use ipis::core::anyhow::{bail, Result};
+
+/// Parses the environment variable if given.
+///
+/// Unlike ignoring the errors of [`ipis::env::infer`], the malformed values are rejected.
+pub fn infer_optional<T>(key: &str) -> Result<Option<T>>
+where
+    T: FromStr,
+    <T as FromStr>::Err: fmt::Display,
+{
+    match ::std::env::var(key) {
+        Ok(value) => match value.parse() {
+            Ok(value) => Ok(Some(value)),
+            Err(e) => bail!("malformed environment variable {key:?}: {e}"),
+        },
+        Err(::std::env::VarError::NotPresent) => Ok(None),
+        Err(e) => bail!("malformed environment variable {key:?}: {e}"),
+    }
+}
@@ -0,0 +1,8 @@
+pub mod client;
+pub mod drain;
+pub mod env;
+pub mod metrics;
+pub mod nonce;
+pub mod policy;
+pub mod quota;
+pub mod storage;
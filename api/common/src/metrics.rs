@@ -0,0 +1,191 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::storage::PoolState;
+
+/// The upper bounds of the latency buckets, in seconds.
+const BUCKETS: [f64; 8] = [0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0];
+
+/// The counters and histograms of the IO calls, rendered in the Prometheus text format.
+#[derive(Default)]
+pub struct Metrics {
+    calls: Mutex<BTreeMap<&'static str, CallMetrics>>,
+    auth_failures: AtomicU64,
+    connection_errors: AtomicU64,
+}
+
+#[derive(Default)]
+struct CallMetrics {
+    succeeded: u64,
+    failed: u64,
+    /// the non-cumulative counts of the latency buckets
+    buckets: [u64; BUCKETS.len()],
+    sum: f64,
+}
+
+impl Metrics {
+    /// Starts measuring the IO call.
+    ///
+    /// The call is counted as failed unless [`CallTimer::succeed`] is called.
+    pub fn start(&self, name: &'static str) -> CallTimer<'_> {
+        CallTimer {
+            metrics: self,
+            name,
+            started: Instant::now(),
+            succeeded: false,
+        }
+    }
+
+    pub fn add_auth_failure(&self) {
+        self.auth_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts the connection to the metrics listener which failed to be read or written.
+    pub fn add_connection_error(&self) {
+        self.connection_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn record(&self, name: &'static str, elapsed: Duration, succeeded: bool) {
+        let elapsed = elapsed.as_secs_f64();
+
+        let mut calls = self.calls.lock().unwrap();
+        let call = calls.entry(name).or_default();
+        if succeeded {
+            call.succeeded += 1;
+        } else {
+            call.failed += 1;
+        }
+        if let Some(index) = BUCKETS.iter().position(|bound| elapsed <= *bound) {
+            call.buckets[index] += 1;
+        }
+        call.sum += elapsed;
+    }
+
+    pub fn render(&self, pool: Option<PoolState>) -> String {
+        let mut buf = String::new();
+        self.render_to(&mut buf, pool)
+            .expect("writing to a string should not fail");
+        buf
+    }
+
+    fn render_to(&self, buf: &mut String, pool: Option<PoolState>) -> ::std::fmt::Result {
+        let calls = self.calls.lock().unwrap();
+
+        writeln!(buf, "# HELP ipdis_calls_total The number of the IO calls.")?;
+        writeln!(buf, "# TYPE ipdis_calls_total counter")?;
+        for (name, call) in calls.iter() {
+            writeln!(
+                buf,
+                "ipdis_calls_total{{call=\"{name}\",result=\"ok\"}} {}",
+                call.succeeded,
+            )?;
+            writeln!(
+                buf,
+                "ipdis_calls_total{{call=\"{name}\",result=\"error\"}} {}",
+                call.failed,
+            )?;
+        }
+
+        writeln!(
+            buf,
+            "# HELP ipdis_call_duration_seconds The latencies of the IO calls."
+        )?;
+        writeln!(buf, "# TYPE ipdis_call_duration_seconds histogram")?;
+        for (name, call) in calls.iter() {
+            let mut count = 0;
+            for (bound, bucket) in BUCKETS.iter().zip(call.buckets) {
+                count += bucket;
+                writeln!(
+                    buf,
+                    "ipdis_call_duration_seconds_bucket{{call=\"{name}\",le=\"{bound}\"}} {count}",
+                )?;
+            }
+
+            let count = call.succeeded + call.failed;
+            writeln!(
+                buf,
+                "ipdis_call_duration_seconds_bucket{{call=\"{name}\",le=\"+Inf\"}} {count}",
+            )?;
+            writeln!(
+                buf,
+                "ipdis_call_duration_seconds_sum{{call=\"{name}\"}} {}",
+                call.sum,
+            )?;
+            writeln!(
+                buf,
+                "ipdis_call_duration_seconds_count{{call=\"{name}\"}} {count}",
+            )?;
+        }
+
+        writeln!(
+            buf,
+            "# HELP ipdis_auth_failures_total The number of the rejected accounts and signatures."
+        )?;
+        writeln!(buf, "# TYPE ipdis_auth_failures_total counter")?;
+        writeln!(
+            buf,
+            "ipdis_auth_failures_total {}",
+            self.auth_failures.load(Ordering::Relaxed),
+        )?;
+
+        writeln!(
+            buf,
+            "# HELP ipdis_metrics_connection_errors_total The number of the failed scrapes."
+        )?;
+        writeln!(buf, "# TYPE ipdis_metrics_connection_errors_total counter")?;
+        writeln!(
+            buf,
+            "ipdis_metrics_connection_errors_total {}",
+            self.connection_errors.load(Ordering::Relaxed),
+        )?;
+
+        if let Some(pool) = pool {
+            writeln!(
+                buf,
+                "# HELP ipdis_pool_connections The number of the connections in the pool."
+            )?;
+            writeln!(buf, "# TYPE ipdis_pool_connections gauge")?;
+            writeln!(buf, "ipdis_pool_connections {}", pool.connections)?;
+
+            writeln!(
+                buf,
+                "# HELP ipdis_pool_idle_connections The number of the idle connections in the pool."
+            )?;
+            writeln!(buf, "# TYPE ipdis_pool_idle_connections gauge")?;
+            writeln!(buf, "ipdis_pool_idle_connections {}", pool.idle_connections)?;
+        }
+        Ok(())
+    }
+}
+
+/// A measurement of an IO call, which is recorded when dropped.
+pub struct CallTimer<'a> {
+    metrics: &'a Metrics,
+    name: &'static str,
+    started: Instant,
+    succeeded: bool,
+}
+
+impl<'a> CallTimer<'a> {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn succeed(mut self) {
+        self.succeeded = true;
+    }
+}
+
+impl<'a> Drop for CallTimer<'a> {
+    fn drop(&mut self) {
+        self.metrics
+            .record(self.name, self.started.elapsed(), self.succeeded)
+    }
+}
@@ -0,0 +1,112 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Mutex,
+};
+
+use ipdis_common::IpdisError;
+use ipis::core::{
+    account::GuaranteeSigned,
+    anyhow::Result,
+    chrono::{NaiveDateTime, Utc},
+};
+
+use crate::env::infer_optional;
+
+/// A bounded cache of the nonces recorded in a storage, put in front of its table.
+///
+/// The cache only answers the replays it has seen, so a miss still goes to the storage.
+/// When it is full, the nonces to be expired first are forgotten.
+pub struct NonceCache {
+    capacity: usize,
+    nonces: Mutex<Nonces>,
+}
+
+/// The guarantee and the nonce of a request.
+type NonceKey = (String, String);
+
+#[derive(Default)]
+struct Nonces {
+    keys: HashSet<NonceKey>,
+    /// the keys of the nonces, by their retention dates
+    expirations: BTreeMap<NaiveDateTime, Vec<NonceKey>>,
+}
+
+impl NonceCache {
+    const DEFAULT_CAPACITY: usize = 100_000;
+
+    pub fn try_infer() -> Result<Self> {
+        let capacity =
+            infer_optional("ipdis_api_nonce_cache_size")?.unwrap_or(Self::DEFAULT_CAPACITY);
+
+        Ok(Self::with_capacity(capacity))
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            capacity,
+            nonces: Default::default(),
+        }
+    }
+
+    fn key_of(request: &GuaranteeSigned) -> NonceKey {
+        (
+            request.guarantee.account.to_string(),
+            request.data.nonce.0 .0.to_string(),
+        )
+    }
+
+    /// Returns `true` if the nonce of the request is known to be recorded.
+    pub fn contains(&self, request: &GuaranteeSigned) -> bool {
+        let key = Self::key_of(request);
+        self.nonces.lock().unwrap().keys.contains(&key)
+    }
+
+    /// Remembers the nonce of the request, which has been recorded in the storage.
+    pub fn insert(&self, request: &GuaranteeSigned, retention_date: NaiveDateTime) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let key = Self::key_of(request);
+        let mut nonces = self.nonces.lock().unwrap();
+        if !nonces.keys.insert(key.clone()) {
+            return;
+        }
+        nonces
+            .expirations
+            .entry(retention_date)
+            .or_default()
+            .push(key);
+
+        // forget the nonces to be expired first, which are still kept in the storage
+        while nonces.keys.len() > self.capacity {
+            let date = match nonces.expirations.keys().next() {
+                Some(date) => *date,
+                None => break,
+            };
+            for key in nonces.expirations.remove(&date).unwrap_or_default() {
+                nonces.keys.remove(&key);
+            }
+        }
+    }
+
+    /// Forgets the expired nonces.
+    pub fn sweep(&self) {
+        let now = Utc::now().naive_utc();
+        let mut nonces = self.nonces.lock().unwrap();
+        let nonces = &mut *nonces;
+
+        let alive = nonces.expirations.split_off(&now);
+        for key in ::core::mem::replace(&mut nonces.expirations, alive)
+            .into_values()
+            .flatten()
+        {
+            nonces.keys.remove(&key);
+        }
+    }
+}
+
+/// The error of the request whose nonce has already been recorded.
+pub fn replayed() -> IpdisError {
+    IpdisError::Conflict("replayed request: the nonce has already been used".into())
+}
@@ -0,0 +1,135 @@
+use ipdis_common::IpdisError;
+use ipis::core::{
+    account::GuaranteeSigned,
+    anyhow::{bail, Result},
+    chrono::{Duration, NaiveDateTime, Utc},
+    metadata::Metadata,
+};
+
+use crate::env::infer_optional;
+
+/// A policy of the lifetimes of the signed requests and records.
+///
+/// The maximum lifetime applies to the requests only; the stored records may never expire.
+#[derive(Copy, Clone, Debug)]
+pub struct RequestPolicy {
+    /// the maximum lifetime of the requests
+    pub max_lifetime: Duration,
+    /// the tolerance of the clocks between the clients and the server
+    pub clock_skew: Duration,
+}
+
+impl Default for RequestPolicy {
+    fn default() -> Self {
+        Self {
+            max_lifetime: Duration::seconds(Self::DEFAULT_MAX_LIFETIME_SECS),
+            clock_skew: Duration::seconds(Self::DEFAULT_CLOCK_SKEW_SECS),
+        }
+    }
+}
+
+impl RequestPolicy {
+    const DEFAULT_MAX_LIFETIME_SECS: i64 = 24 * 60 * 60;
+    const DEFAULT_CLOCK_SKEW_SECS: i64 = 30;
+
+    pub fn try_infer() -> Result<Self> {
+        let max_lifetime: i64 = infer_optional("ipdis_policy_max_lifetime_secs")?
+            .unwrap_or(Self::DEFAULT_MAX_LIFETIME_SECS);
+        let clock_skew: i64 = infer_optional("ipdis_policy_clock_skew_secs")?
+            .unwrap_or(Self::DEFAULT_CLOCK_SKEW_SECS);
+
+        if max_lifetime <= 0 {
+            bail!("malformed policy: the maximum lifetime should be positive")
+        }
+        if clock_skew < 0 {
+            bail!("malformed policy: the clock skew should not be negative")
+        }
+
+        Ok(Self {
+            max_lifetime: Duration::seconds(max_lifetime),
+            clock_skew: Duration::seconds(clock_skew),
+        })
+    }
+
+    /// Ensures that the record is not created in the future.
+    pub fn ensure_created(&self, metadata: &Metadata) -> Result<()> {
+        let now = Utc::now().naive_utc();
+
+        if metadata.created_date.naive_utc() > now + self.clock_skew {
+            bail!(IpdisError::Expired(
+                "not yet valid: the record is created in the future".into(),
+            ))
+        }
+        Ok(())
+    }
+
+    /// Ensures that the request is neither expired nor too long-lived.
+    pub fn ensure_alive(&self, request: &GuaranteeSigned) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let metadata = &request.data;
+        self.ensure_created(metadata)?;
+
+        let created_date = metadata.created_date.naive_utc();
+
+        let expiration_date = match metadata.expiration_date {
+            Some(expiration_date) => expiration_date.naive_utc(),
+            None => bail!(IpdisError::Expired(
+                "never expiring: the request should have an expiration date".into(),
+            )),
+        };
+
+        if expiration_date + self.clock_skew < now {
+            bail!(IpdisError::Expired(format!(
+                "expired: the request has been expired at {expiration_date}",
+            )))
+        }
+        if expiration_date - created_date > self.max_lifetime {
+            bail!(IpdisError::Expired(format!(
+                "too long-lived: the request should expire within {}s",
+                self.max_lifetime.num_seconds(),
+            )))
+        }
+        Ok(())
+    }
+
+    /// Ensures that the record is signed lately and not expired yet.
+    ///
+    /// Unlike the requests, the records may live longer than the maximum lifetime, or never expire;
+    /// only their signing is limited, so that their nonces are kept long enough.
+    pub fn ensure_recent(&self, record: &GuaranteeSigned) -> Result<()> {
+        let now = Utc::now().naive_utc();
+        let metadata = &record.data;
+        self.ensure_created(metadata)?;
+
+        let created_date = metadata.created_date.naive_utc();
+        if created_date + self.max_lifetime + self.clock_skew < now {
+            bail!(IpdisError::Expired(format!(
+                "too old: the record should be put within {}s after signed",
+                self.max_lifetime.num_seconds(),
+            )))
+        }
+        if let Some(expiration_date) = metadata.expiration_date {
+            let expiration_date = expiration_date.naive_utc();
+            if expiration_date + self.clock_skew < now {
+                bail!(IpdisError::Expired(format!(
+                    "expired: the record has been expired at {expiration_date}",
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the date until when the nonce of the request should be kept.
+    ///
+    /// The retention is bounded by the maximum lifetime, even if the request never expires.
+    pub fn retention_date(&self, request: &GuaranteeSigned) -> NaiveDateTime {
+        let metadata = &request.data;
+        let max_expiration_date = metadata.created_date.naive_utc() + self.max_lifetime;
+
+        let expiration_date = match metadata.expiration_date {
+            Some(expiration_date) => expiration_date.naive_utc().min(max_expiration_date),
+            None => max_expiration_date,
+        };
+        expiration_date + self.clock_skew
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use ipdis_common::IpdisError;
+use ipis::core::{
+    account::AccountRef,
+    anyhow::{bail, Result},
+};
+
+use crate::env::infer_optional;
+
+/// A policy of the limits of each guarantee.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct QuotaPolicy {
+    /// the rate of the IO calls, unlimited if not given
+    pub rate: Option<RateLimit>,
+    /// the maximum number of the words put by a guarantee, unlimited if not given
+    pub max_words: Option<u64>,
+    /// the maximum number of the dynamic paths put by a guarantee, unlimited if not given
+    pub max_dyn_paths: Option<u64>,
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct RateLimit {
+    /// the number of the requests refilled per second
+    pub per_sec: u32,
+    /// the number of the requests which can be sent at once
+    pub burst: u32,
+}
+
+impl QuotaPolicy {
+    pub fn try_infer() -> Result<Self> {
+        let rate_per_sec: Option<u32> = infer_optional("ipdis_quota_rate_per_sec")?;
+        let rate_burst: Option<u32> = infer_optional("ipdis_quota_rate_burst")?;
+        let max_words: Option<u64> = infer_optional("ipdis_quota_max_words")?;
+        let max_dyn_paths: Option<u64> = infer_optional("ipdis_quota_max_dyn_paths")?;
+
+        let rate = match rate_per_sec {
+            Some(0) => bail!("malformed quota: the rate should be positive"),
+            Some(per_sec) => Some(RateLimit {
+                per_sec,
+                burst: rate_burst.unwrap_or(per_sec),
+            }),
+            None => None,
+        };
+        if rate.map(|rate| rate.burst == 0).unwrap_or_default() {
+            bail!("malformed quota: the burst should be positive")
+        }
+
+        Ok(Self {
+            rate,
+            max_words,
+            max_dyn_paths,
+        })
+    }
+
+    /// Ensures that the guarantee can put more words and dynamic paths.
+    pub fn ensure_available(&self, usage: &Usage, words: u64, dyn_paths: u64) -> Result<()> {
+        if let Some(max_words) = self.max_words {
+            if words > 0 && usage.words + words > max_words {
+                bail!(self.exhausted_words())
+            }
+        }
+        if let Some(max_dyn_paths) = self.max_dyn_paths {
+            if dyn_paths > 0 && usage.dyn_paths + dyn_paths > max_dyn_paths {
+                bail!(IpdisError::Exhausted(format!(
+                    "quota exceeded: the guarantee can put up to {max_dyn_paths} dynamic paths",
+                )))
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the error of the words which are put over the quota.
+    pub fn exhausted_words(&self) -> IpdisError {
+        IpdisError::Exhausted(format!(
+            "quota exceeded: the guarantee can put up to {} words",
+            self.max_words.unwrap_or_default(),
+        ))
+    }
+}
+
+/// Counts the records to be put by each guarantee, in the order of their first appearance.
+pub fn count_by_guarantee<'a>(
+    guarantees: impl IntoIterator<Item = &'a AccountRef>,
+) -> Vec<(&'a AccountRef, u64)> {
+    let mut counts: Vec<(&AccountRef, u64)> = vec![];
+    for guarantee in guarantees {
+        match counts.iter_mut().find(|(counted, _)| *counted == guarantee) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((guarantee, 1)),
+        }
+    }
+    counts
+}
+
+/// The number of the records put by a guarantee.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub words: u64,
+    pub dyn_paths: u64,
+}
+
+/// The token buckets of the guarantees, one for each IO call.
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: Mutex<Buckets>,
+}
+
+/// The guarantee and the IO call of a bucket.
+type BucketKey = (String, &'static str);
+
+#[derive(Default)]
+struct Buckets {
+    buckets: HashMap<BucketKey, Bucket>,
+    /// the keys of the buckets, the least recently used first
+    recency: BTreeMap<u64, BucketKey>,
+    /// the counter of the uses, which grows monotonically
+    tick: u64,
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+    /// the last use of the bucket
+    tick: u64,
+}
+
+impl Bucket {
+    fn refill(&mut self, rate: &RateLimit, now: Instant) {
+        let elapsed = now.duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * f64::from(rate.per_sec)).min(f64::from(rate.burst));
+        self.updated = now;
+    }
+}
+
+impl Buckets {
+    /// Returns the bucket of the key, marking it as the most recently used.
+    fn touch(&mut self, rate: &RateLimit, key: BucketKey, now: Instant) -> &mut Bucket {
+        self.tick += 1;
+        let tick = self.tick;
+
+        // forget the idle buckets, which are full again as the missing ones,
+        // and the least recently used ones above the limit
+        let ttl = Duration::from_secs_f64(f64::from(rate.burst) / f64::from(rate.per_sec.max(1)));
+        let is_new = !self.buckets.contains_key(&key);
+        while let Some((&oldest, oldest_key)) = self.recency.iter().next() {
+            let is_idle = self.buckets[oldest_key].updated + ttl <= now;
+            let is_crowded = is_new && self.buckets.len() >= RateLimiter::MAX_BUCKETS;
+            if !is_idle && !is_crowded {
+                break;
+            }
+
+            if let Some(oldest_key) = self.recency.remove(&oldest) {
+                self.buckets.remove(&oldest_key);
+            }
+        }
+
+        let bucket = self.buckets.entry(key.clone()).or_insert_with(|| Bucket {
+            tokens: f64::from(rate.burst),
+            updated: now,
+            tick,
+        });
+        self.recency.remove(&bucket.tick);
+        self.recency.insert(tick, key);
+        bucket.tick = tick;
+
+        bucket.refill(rate, now);
+        bucket
+    }
+}
+
+impl RateLimiter {
+    /// The maximum number of the buckets, above which the least recently used ones are forgotten.
+    const MAX_BUCKETS: usize = 10_000;
+
+    /// Takes a token of the guarantee for the IO call.
+    pub fn acquire(
+        &self,
+        rate: &RateLimit,
+        guarantee: &AccountRef,
+        call: &'static str,
+    ) -> Result<()> {
+        let mut buckets = self.buckets.lock().unwrap();
+        let bucket = buckets.touch(rate, (guarantee.to_string(), call), Instant::now());
+
+        if bucket.tokens < 1.0 {
+            bail!(IpdisError::Exhausted(format!(
+                "rate limited: the guarantee can send up to {} {call} requests per second",
+                rate.per_sec,
+            )))
+        }
+        bucket.tokens -= 1.0;
+        Ok(())
+    }
+
+    /// Returns the number of the requests which the guarantee can send right now,
+    /// by the IO call which it has used the most.
+    pub fn remaining(&self, rate: &RateLimit, guarantee: &AccountRef) -> u32 {
+        let now = Instant::now();
+        let guarantee = guarantee.to_string();
+
+        let mut buckets = self.buckets.lock().unwrap();
+        buckets
+            .buckets
+            .iter_mut()
+            .filter(|((owner, _), _)| owner == &guarantee)
+            .map(|(_, bucket)| {
+                bucket.refill(rate, now);
+                bucket.tokens as u32
+            })
+            .min()
+            .unwrap_or(rate.burst)
+    }
+}
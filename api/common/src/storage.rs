@@ -0,0 +1,272 @@
+use std::time::Duration;
+
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWords, GetWordsCounts, GetWordsCountsOutput, GuaranteeScope,
+    NamespaceStats, Page, PutWordsError,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{bail, Result},
+        chrono::{NaiveDateTime, Utc},
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
+    path::{DynPath, Path},
+    word::WordHash,
+};
+
+use crate::{
+    env::infer_optional,
+    policy::RequestPolicy,
+    quota::{QuotaPolicy, Usage},
+};
+
+/// The sizes and the timeouts of the connection pool of a storage.
+///
+/// The defaults of the pool are kept for the values not given.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PoolConfig {
+    pub max_size: Option<u32>,
+    pub min_idle: Option<u32>,
+    pub connection_timeout: Option<Duration>,
+    pub idle_timeout: Option<Duration>,
+}
+
+impl PoolConfig {
+    pub fn try_infer() -> Result<Self> {
+        let max_size: Option<u32> = infer_optional("ipdis_api_pool_max_size")?;
+        let min_idle: Option<u32> = infer_optional("ipdis_api_pool_min_idle")?;
+        let connection_timeout: Option<u64> =
+            infer_optional("ipdis_api_pool_connection_timeout_secs")?;
+        let idle_timeout: Option<u64> = infer_optional("ipdis_api_pool_idle_timeout_secs")?;
+
+        if max_size == Some(0) {
+            bail!("malformed pool: the maximum size should be positive")
+        }
+        if let (Some(max_size), Some(min_idle)) = (max_size, min_idle) {
+            if min_idle > max_size {
+                bail!("malformed pool: the idle connections should not exceed the maximum size")
+            }
+        }
+        if connection_timeout == Some(0) {
+            bail!("malformed pool: the connection timeout should be positive")
+        }
+
+        Ok(Self {
+            max_size,
+            min_idle,
+            connection_timeout: connection_timeout.map(Duration::from_secs),
+            idle_timeout: idle_timeout.map(Duration::from_secs),
+        })
+    }
+}
+
+/// Runs the blocking operations of a storage, such as the synchronous queries.
+///
+/// The operations run on the blocking threads, so that the executor is not blocked
+/// even if the runtime has only one thread.
+#[cfg(not(feature = "inline-blocking"))]
+pub async fn blocking<R>(f: impl FnOnce() -> Result<R> + Send + 'static) -> Result<R>
+where
+    R: Send + 'static,
+{
+    ::ipis::tokio::task::spawn_blocking(f).await?
+}
+
+/// Runs the blocking operations of a storage on the executor, as the synchronous baseline.
+#[cfg(feature = "inline-blocking")]
+pub async fn blocking<R>(f: impl FnOnce() -> Result<R> + Send + 'static) -> Result<R>
+where
+    R: Send + 'static,
+{
+    f()
+}
+
+/// A snapshot of the connections of a storage.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PoolState {
+    pub connections: u32,
+    pub idle_connections: u32,
+}
+
+/// A persistent store of the records signed by both the guarantee and the guarantor.
+///
+/// The storage does not authenticate anything by itself;
+/// it is the caller's duty to verify the accounts before touching the records.
+#[async_trait]
+pub trait IpdisStorage
+where
+    Self: Send + Sync,
+{
+    async fn try_infer() -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Opens the storage without migrating it, e.g. to check the pending migrations.
+    async fn try_infer_verify_only() -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::try_infer().await
+    }
+
+    /// Connects to the storage, and returns the number of the migrations not applied yet.
+    async fn get_pending_migrations(&self) -> Result<u32> {
+        Ok(0)
+    }
+
+    /// Returns the number of the records put by the guarantee.
+    async fn get_usage(&self, guarantee: &AccountRef) -> Result<Usage>;
+
+    /// Returns the state of the connection pool, if the storage has one.
+    fn pool_state(&self) -> Option<PoolState> {
+        None
+    }
+
+    /// Checks whether the guarantee is alive and created after its latest revocation.
+    async fn contains_guarantee(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool>;
+
+    async fn put_guarantee(&self, guarantee: &Data<GuarantorSigned, AccountRef>) -> Result<()>;
+
+    /// Returns the guarantees which are neither expired nor revoked, the latest first.
+    async fn get_guarantee_many(
+        &self,
+        guarantor: &AccountRef,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>>;
+
+    /// Erases the guarantees of the account given by any guarantor, and their scopes.
+    ///
+    /// The revocations are kept, as they are the signed records of the guarantors.
+    async fn delete_guarantee_all(&self, guarantee: &AccountRef) -> Result<()>;
+
+    /// Returns the last revocation put, which revokes the guarantees put before it.
+    async fn get_guarantee_revocation_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, AccountRef>>>;
+
+    async fn put_guarantee_revocation(
+        &self,
+        revocation: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()>;
+
+    /// Returns the latest scope of the guarantee, given by the guarantor.
+    async fn get_guarantee_scope_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, GuaranteeScope>>>;
+
+    async fn put_guarantee_scope(
+        &self,
+        scope: &Data<GuarantorSigned, GuaranteeScope>,
+    ) -> Result<()>;
+
+    /// Records the nonce of the signed request until the retention date.
+    ///
+    /// Returns `false` if the nonce has already been recorded for the guarantee.
+    async fn put_nonce(
+        &self,
+        request: &GuaranteeSigned,
+        retention_date: NaiveDateTime,
+    ) -> Result<bool>;
+
+    /// Forgets the nonces past their retention dates, which is called periodically.
+    async fn sweep_nonces(&self) -> Result<()>;
+
+    async fn get_dyn_path(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>>;
+
+    async fn get_dyn_path_at(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>>;
+
+    /// Returns all the versions of the dynamic path, including the expired ones.
+    async fn get_dyn_path_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>>>;
+
+    /// Puts the dynamic path, if the guarantee has not exhausted its quota.
+    ///
+    /// The nonce of the path is recorded along with it, so that a failed put can be retried.
+    async fn put_dyn_path(
+        &self,
+        path: &Data<GuarantorSigned, DynPath<Path>>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()>;
+
+    async fn delete_dyn_path_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()>;
+
+    async fn get_word_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>>>;
+
+    async fn get_word_count_many(
+        &self,
+        guarantee: &AccountRef,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>>;
+
+    async fn get_namespace_stats(&self, namespace: &Hash) -> Result<NamespaceStats>;
+
+    async fn put_word(
+        &self,
+        parent: &Hash,
+        word: &Data<GuarantorSigned, WordHash>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()> {
+        match self
+            .put_word_many(&[(*parent, *word)], quota, policy)
+            .await?
+            .pop()
+        {
+            Some(error) => bail!(error.error),
+            None => Ok(()),
+        }
+    }
+
+    /// Puts the words at once, and returns the ones which have been rejected.
+    ///
+    /// The rejected words are skipped, and either all the others are put, or none of them.
+    /// The words are counted against the quota of their guarantees atomically with the put,
+    /// so that the concurrent puts cannot exceed it.
+    /// Likewise, the nonces of the words are recorded along with them,
+    /// and the words whose nonces have already been recorded are rejected.
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuarantorSigned, WordHash>)],
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<Vec<PutWordsError>>;
+
+    async fn delete_word_all(&self, guarantee: Option<&AccountRef>, namespace: &Hash)
+        -> Result<()>;
+}
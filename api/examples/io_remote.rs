@@ -3,7 +3,7 @@ use ipdis_api::{
     common::{Ipdis, KIND},
     server::IpdisServer,
 };
-use ipdis_common::{GetWords, GetWordsCounts, GetWordsParent};
+use ipdis_common::{GetWords, GetWordsCounts, GetWordsParent, IpdisError};
 use ipiis_api::{client::IpiisClient, common::Ipiis, server::IpiisServer};
 use ipis::{
     core::{
@@ -82,6 +82,15 @@ async fn main() -> Result<()> {
         client.add_guarantee(&guarantee).await?;
     };
 
+    // ensure that the client has been registered
+    assert_eq!(
+        client
+            .get_guarantee_unchecked(client_account)
+            .await?
+            .data,
+        *client_account,
+    );
+
     // create a sample word to be stored
     let namespace = "ipdis-api-postgres-test";
     let kind = "ipdis-api-postgres-test";
@@ -120,7 +129,7 @@ async fn main() -> Result<()> {
 
     // cleanup test data
     client_guarantor
-        .delete_word_all_unchecked(&word.key.namespace)
+        .delete_word_all_unchecked(None, &word.key.namespace)
         .await
         .unwrap();
 
@@ -131,7 +140,21 @@ async fn main() -> Result<()> {
 
     // get the dynamic path
     let dyn_path_from_ipdis = client.get_dyn_path_unchecked(None, &dyn_path).await?;
-    assert_eq!(&dyn_path_from_ipdis.unwrap().data, &dyn_path);
+    assert_eq!(&dyn_path_from_ipdis.data, &dyn_path);
+
+    // delete the dynamic paths signed by the client
+    client
+        .delete_dyn_path_all_unchecked(None, &dyn_path.namespace)
+        .await?;
+
+    // ensure that the dynamic path has been deleted
+    assert!(matches!(
+        client
+            .get_dyn_path_unchecked(None, &dyn_path)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::NotFound(_)),
+    ));
 
     // put the word in IPDIS (* 3 times)
     let count = 3u32;
@@ -157,13 +180,14 @@ async fn main() -> Result<()> {
             &GetWords {
                 word: parent_word.key,
                 parent: GetWordsParent::Duplicated,
+                cursor: None,
                 start_index: 0,
                 end_index: 1,
             },
         )
         .await
         .unwrap();
-    assert_eq!(&words_from_ipdis[0].data, &word);
+    assert_eq!(&words_from_ipdis.items[0].data, &word);
 
     // get the word counts
     let count_from_ipdis = client
@@ -186,12 +210,14 @@ async fn main() -> Result<()> {
                     word: parent_word.key,
                     parent: true,
                     owned: false,
+                    cursor: None,
                     start_index: 0,
                     end_index: 1,
                 }
             )
             .await
             .unwrap()
+            .items
             .pop()
             .unwrap()
             .count,
@@ -207,24 +233,46 @@ async fn main() -> Result<()> {
                     word: parent_word.key,
                     parent: true,
                     owned: true,
+                    cursor: None,
                     start_index: 0,
                     end_index: 1,
                 }
             )
             .await
             .unwrap()
+            .items
             .pop()
             .unwrap()
             .count,
         count,
     );
 
+    // revoke the client's guarantee (only the guarantee or the guarantor can perform it)
+    client
+        .revoke_guarantee_unchecked(&client.sign_owned(server_account, *client_account)?)
+        .await?;
+
+    // ensure that the revoked client is no longer registered
+    assert!(matches!(
+        client
+            .get_guarantee_unchecked(client_account)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::NotFound(_)),
+    ));
+
+    // ensure that the revoked client cannot access IPDIS anymore
+    assert!(client
+        .get_dyn_path_unchecked(None, &dyn_path)
+        .await
+        .is_err());
+
     // cleanup test data
     client_guarantor
         .delete_guarantee_unchecked(client_account)
         .await?;
     client_guarantor
-        .delete_word_all_unchecked(&word.key.namespace)
+        .delete_word_all_unchecked(None, &word.key.namespace)
         .await?;
 
     // ensure that the guarantee client has been unregistered
@@ -0,0 +1,3 @@
+mod storage;
+
+pub use self::storage::MemoryStorage;
@@ -0,0 +1,750 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use ipdis_api_common::{
+    nonce,
+    policy::RequestPolicy,
+    quota::{count_by_guarantee, QuotaPolicy, Usage},
+    storage::IpdisStorage,
+};
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWordKeyHash, GetWords, GetWordsCounts, GetWordsCountsOutput,
+    GetWordsParent, GuaranteeScope, NamespaceStats, Page, PutWordsError, SignedCursor,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{bail, Result},
+        chrono::{NaiveDateTime, Utc},
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
+    path::{DynPath, Path},
+    tokio::sync::RwLock,
+    word::WordHash,
+};
+
+/// A volatile storage which keeps all the records in the process memory.
+///
+/// All the records are lost when the storage is dropped.
+#[derive(Default)]
+pub struct MemoryStorage {
+    /// the ids in the order put, shared by the guarantees and their revocations
+    ids: AtomicU64,
+    guarantees: RwLock<Vec<(u64, Data<GuarantorSigned, AccountRef>)>>,
+    guarantees_revocations: RwLock<Vec<(u64, Data<GuarantorSigned, AccountRef>)>>,
+    guarantees_scopes: RwLock<Vec<Data<GuarantorSigned, GuaranteeScope>>>,
+    nonces: RwLock<Nonces>,
+    dyn_paths: RwLock<DynPaths>,
+    words: RwLock<Words>,
+}
+
+#[derive(Default)]
+struct Nonces {
+    /// the guarantees and the nonces, which are alive
+    keys: HashSet<(String, String)>,
+    /// the keys of the nonces, by their retention dates
+    expirations: BTreeMap<NaiveDateTime, Vec<(String, String)>>,
+}
+
+impl Nonces {
+    fn key_of(request: &GuaranteeSigned) -> (String, String) {
+        (
+            request.guarantee.account.to_string(),
+            request.data.nonce.0 .0.to_string(),
+        )
+    }
+
+    fn contains(&self, request: &GuaranteeSigned) -> bool {
+        self.keys.contains(&Self::key_of(request))
+    }
+
+    /// Inserts the nonce only if it is not recorded yet.
+    fn insert(&mut self, request: &GuaranteeSigned, retention_date: NaiveDateTime) -> bool {
+        let key = Self::key_of(request);
+        if !self.keys.insert(key.clone()) {
+            return false;
+        }
+
+        self.expirations
+            .entry(retention_date)
+            .or_default()
+            .push(key);
+        true
+    }
+
+    /// Forgets the expired nonces.
+    fn sweep(&mut self) {
+        let now = Utc::now().naive_utc();
+
+        let alive = self.expirations.split_off(&now);
+        for key in ::core::mem::replace(&mut self.expirations, alive)
+            .into_values()
+            .flatten()
+        {
+            self.keys.remove(&key);
+        }
+    }
+}
+
+#[derive(Default)]
+struct DynPaths {
+    /// the id of the next record, which grows monotonically
+    next_id: u64,
+    records: Vec<DynPathRecord>,
+}
+
+struct DynPathRecord {
+    id: u64,
+    path: Data<GuarantorSigned, DynPath<Path>>,
+}
+
+#[derive(Default)]
+struct Words {
+    /// the id of the next record, which grows monotonically
+    next_id: u64,
+    records: Vec<WordRecord>,
+    counts: Vec<WordCount>,
+    counts_guarantees: Vec<WordCount>,
+}
+
+struct WordRecord {
+    id: u64,
+    parent: Hash,
+    word: Data<GuarantorSigned, WordHash>,
+}
+
+struct WordCount {
+    id: u64,
+    guarantee: Option<AccountRef>,
+    parent: Hash,
+    word: GetWordKeyHash,
+    count: u32,
+}
+
+impl WordCount {
+    fn increment(
+        counts: &mut Vec<Self>,
+        id: u64,
+        guarantee: Option<&AccountRef>,
+        parent: &Hash,
+        word: &GetWordKeyHash,
+    ) {
+        match counts.iter_mut().find(|count| {
+            count.guarantee.as_ref() == guarantee && &count.parent == parent && &count.word == word
+        }) {
+            // old word => append the count
+            Some(count) => count.count += 1,
+            // new word => insert the word record
+            None => counts.push(Self {
+                id,
+                guarantee: guarantee.copied(),
+                parent: *parent,
+                word: *word,
+                count: 1,
+            }),
+        }
+    }
+
+    fn is_matched(&self, query: &GetWordsCounts) -> bool {
+        is_after(self.id, query.cursor.as_ref())
+            && self.word.key.namespace == query.word.namespace
+            && self.word.key.text.lang == query.word.text.lang
+            && if query.parent {
+                self.parent == query.word.text.msg
+            } else {
+                self.word.key.text.msg == query.word.text.msg
+            }
+    }
+}
+
+impl From<&WordCount> for GetWordsCountsOutput {
+    fn from(count: &WordCount) -> Self {
+        Self {
+            word: count.word,
+            count: count.count,
+        }
+    }
+}
+
+#[async_trait]
+impl IpdisStorage for MemoryStorage {
+    async fn try_infer() -> Result<Self> {
+        Ok(Self::default())
+    }
+
+    async fn contains_guarantee(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        let now = Utc::now().naive_utc();
+        let revocations = self.guarantees_revocations.read().await;
+
+        Ok(self.guarantees.read().await.iter().any(|(id, record)| {
+            &record.metadata.guarantee.account == guarantee
+                && &record.metadata.guarantor.account == guarantor
+                && is_alive(record, &now)
+                && !is_revoked(&revocations, *id, record)
+        }))
+    }
+
+    async fn put_guarantee(&self, guarantee: &Data<GuarantorSigned, AccountRef>) -> Result<()> {
+        let mut guarantees = self.guarantees.write().await;
+        guarantees.push((self.ids.fetch_add(1, Ordering::SeqCst), *guarantee));
+        Ok(())
+    }
+
+    async fn get_guarantee_many(
+        &self,
+        guarantor: &AccountRef,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        let now = Utc::now().naive_utc();
+        let revocations = self.guarantees_revocations.read().await;
+
+        Ok(self
+            .guarantees
+            .read()
+            .await
+            .iter()
+            // the latest one comes first
+            .rev()
+            .filter(|(id, record)| {
+                &record.metadata.guarantor.account == guarantor
+                    && match &query.guarantee {
+                        Some(guarantee) => &record.metadata.guarantee.account == guarantee,
+                        None => true,
+                    }
+                    && is_alive(record, &now)
+                    && !is_revoked(&revocations, *id, record)
+            })
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .map(|(_, record)| *record)
+            .collect())
+    }
+
+    async fn delete_guarantee_all(&self, guarantee: &AccountRef) -> Result<()> {
+        self.guarantees
+            .write()
+            .await
+            .retain(|(_, record)| &record.metadata.guarantee.account != guarantee);
+
+        // the scopes are given to the guarantees, so they are deleted together
+        self.guarantees_scopes
+            .write()
+            .await
+            .retain(|record| &record.data.account != guarantee);
+        Ok(())
+    }
+
+    async fn get_guarantee_revocation_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, AccountRef>>> {
+        Ok(self
+            .guarantees_revocations
+            .read()
+            .await
+            .iter()
+            // the latest one is the last one put, not the last one signed
+            .rev()
+            .find(|(_, record)| {
+                &record.data == guarantee && &record.metadata.guarantor.account == guarantor
+            })
+            .map(|(_, record)| *record))
+    }
+
+    async fn put_guarantee_revocation(
+        &self,
+        revocation: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()> {
+        let mut revocations = self.guarantees_revocations.write().await;
+        revocations.push((self.ids.fetch_add(1, Ordering::SeqCst), *revocation));
+        Ok(())
+    }
+
+    async fn get_guarantee_scope_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, GuaranteeScope>>> {
+        Ok(self
+            .guarantees_scopes
+            .read()
+            .await
+            .iter()
+            // the latest one is the last one put, not the last one signed
+            .rev()
+            .find(|record| {
+                &record.data.account == guarantee && &record.metadata.guarantor.account == guarantor
+            })
+            .cloned())
+    }
+
+    async fn put_guarantee_scope(
+        &self,
+        scope: &Data<GuarantorSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        self.guarantees_scopes.write().await.push(scope.clone());
+        Ok(())
+    }
+
+    async fn put_nonce(
+        &self,
+        request: &GuaranteeSigned,
+        retention_date: NaiveDateTime,
+    ) -> Result<bool> {
+        Ok(self.nonces.write().await.insert(request, retention_date))
+    }
+
+    async fn sweep_nonces(&self) -> Result<()> {
+        self.nonces.write().await.sweep();
+        Ok(())
+    }
+
+    async fn get_dyn_path(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let now = Utc::now().naive_utc();
+
+        Ok(self
+            .dyn_paths
+            .read()
+            .await
+            .records
+            .iter()
+            .map(|record| &record.path)
+            .filter(|record| {
+                &record.metadata.guarantee.account == guarantee
+                    && &record.metadata.guarantor.account == guarantor
+                    && is_alive(record, &now)
+                    && record.data.namespace == path.namespace
+                    && record.data.kind == path.kind
+                    && record.data.word == path.word
+            })
+            // the latest one wins
+            .max_by_key(|record| record.metadata.created_date.naive_utc())
+            .copied())
+    }
+
+    async fn get_dyn_path_at(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let timestamp = timestamp.naive_utc();
+
+        Ok(self
+            .dyn_paths
+            .read()
+            .await
+            .records
+            .iter()
+            .map(|record| &record.path)
+            .filter(|record| {
+                &record.metadata.guarantee.account == guarantee
+                    && &record.metadata.guarantor.account == guarantor
+                    && record.metadata.created_date.naive_utc() <= timestamp
+                    && is_alive(record, &timestamp)
+                    && record.data.namespace == path.namespace
+                    && record.data.kind == path.kind
+                    && record.data.word == path.word
+            })
+            // the latest one wins
+            .max_by_key(|record| record.metadata.created_date.naive_utc())
+            .copied())
+    }
+
+    async fn get_dyn_path_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>>> {
+        let records = self
+            .dyn_paths
+            .read()
+            .await
+            .records
+            .iter()
+            // the latest one comes first
+            .rev()
+            .filter(|record| {
+                let path = &record.path;
+                let created_date = path.metadata.created_date.naive_utc();
+
+                is_after(record.id, query.cursor.as_ref())
+                    && &path.metadata.guarantee.account == guarantee
+                    && &path.metadata.guarantor.account == guarantor
+                    && path.data.namespace == query.path.namespace
+                    && path.data.kind == query.path.kind
+                    && path.data.word == query.path.word
+                    && match &query.since {
+                        Some(since) => created_date >= since.naive_utc(),
+                        None => true,
+                    }
+                    && match &query.until {
+                        Some(until) => created_date <= until.naive_utc(),
+                        None => true,
+                    }
+            })
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .map(|record| (record.id, record.path))
+            .collect();
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn put_dyn_path(
+        &self,
+        path: &Data<GuarantorSigned, DynPath<Path>>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()> {
+        let mut dyn_paths = self.dyn_paths.write().await;
+        let mut nonces = self.nonces.write().await;
+
+        // count under the lock, so that the concurrent puts cannot exceed the quota
+        if quota.max_dyn_paths.is_some() {
+            let guarantee = &path.metadata.guarantee.account;
+            let usage = Usage {
+                words: 0,
+                dyn_paths: dyn_paths
+                    .records
+                    .iter()
+                    .filter(|record| &record.path.metadata.guarantee.account == guarantee)
+                    .count()
+                    .try_into()?,
+            };
+            quota.ensure_available(&usage, 0, 1)?;
+        }
+
+        // record the nonce along with the path
+        if !nonces.insert(&path.metadata, policy.retention_date(&path.metadata)) {
+            bail!(nonce::replayed())
+        }
+
+        let id = dyn_paths.next_id;
+        dyn_paths.next_id += 1;
+
+        dyn_paths.records.push(DynPathRecord { id, path: *path });
+        Ok(())
+    }
+
+    async fn delete_dyn_path_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        self.dyn_paths.write().await.records.retain(|record| {
+            &record.path.data.namespace != namespace
+                || guarantee
+                    .map(|guarantee| &record.path.metadata.guarantee.account != guarantee)
+                    .unwrap_or_default()
+        });
+        Ok(())
+    }
+
+    async fn get_word_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>>> {
+        let now = Utc::now().naive_utc();
+
+        let records = self
+            .words
+            .read()
+            .await
+            .records
+            .iter()
+            // the latest one comes first
+            .rev()
+            .filter(|record| {
+                let word = &record.word;
+
+                is_after(record.id, query.cursor.as_ref())
+                    && &word.metadata.guarantee.account == guarantee
+                    && &word.metadata.guarantor.account == guarantor
+                    && is_alive(word, &now)
+                    && word.data.key.namespace == query.word.namespace
+                    && word.data.key.text.lang == query.word.text.lang
+                    && match query.parent {
+                        GetWordsParent::None => word.data.key.text.msg == query.word.text.msg,
+                        GetWordsParent::Duplicated => record.parent == query.word.text.msg,
+                    }
+            })
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .map(|record| (record.id, record.word))
+            .collect();
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn get_word_count_many(
+        &self,
+        guarantee: &AccountRef,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        let words = self.words.read().await;
+
+        let skip = query.start_index as usize;
+        let take = (query.end_index - query.start_index) as usize;
+
+        let records = if query.owned {
+            words
+                .counts_guarantees
+                .iter()
+                .rev()
+                .filter(|count| count.guarantee.as_ref() == Some(guarantee))
+                .filter(|count| count.is_matched(query))
+                .skip(skip)
+                .take(take)
+                .map(|count| (count.id, count.into()))
+                .collect()
+        } else {
+            words
+                .counts
+                .iter()
+                .rev()
+                .filter(|count| count.is_matched(query))
+                .skip(skip)
+                .take(take)
+                .map(|count| (count.id, count.into()))
+                .collect()
+        };
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn get_namespace_stats(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        let words = self.words.read().await;
+
+        let mut total_words = 0u64;
+        let mut distinct_words = vec![];
+        let mut distinct_guarantees = vec![];
+        for record in words
+            .records
+            .iter()
+            .filter(|record| &record.word.data.key.namespace == namespace)
+        {
+            total_words += 1;
+
+            let word = &record.word.data.key.text;
+            if !distinct_words.contains(&word) {
+                distinct_words.push(word);
+            }
+
+            let guarantee = &record.word.metadata.guarantee.account;
+            if !distinct_guarantees.contains(&guarantee) {
+                distinct_guarantees.push(guarantee);
+            }
+        }
+
+        Ok(NamespaceStats {
+            total_words,
+            distinct_words: distinct_words.len().try_into()?,
+            distinct_guarantees: distinct_guarantees.len().try_into()?,
+        })
+    }
+
+    async fn get_usage(&self, guarantee: &AccountRef) -> Result<Usage> {
+        let words = self
+            .words
+            .read()
+            .await
+            .records
+            .iter()
+            .filter(|record| &record.word.metadata.guarantee.account == guarantee)
+            .count();
+        let dyn_paths = self
+            .dyn_paths
+            .read()
+            .await
+            .records
+            .iter()
+            .filter(|record| &record.path.metadata.guarantee.account == guarantee)
+            .count();
+
+        Ok(Usage {
+            words: words.try_into()?,
+            dyn_paths: dyn_paths.try_into()?,
+        })
+    }
+
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuarantorSigned, WordHash>)],
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<Vec<PutWordsError>> {
+        let mut store = self.words.write().await;
+        let store = &mut *store;
+        let mut nonces = self.nonces.write().await;
+
+        // count under the lock, so that the concurrent puts cannot exceed the quota
+        let mut available = vec![];
+        if let Some(max_words) = quota.max_words {
+            let guarantees = words
+                .iter()
+                .map(|(_, word)| &word.metadata.guarantee.account);
+            for (guarantee, _) in count_by_guarantee(guarantees) {
+                let count: u64 = store
+                    .records
+                    .iter()
+                    .filter(|record| &record.word.metadata.guarantee.account == guarantee)
+                    .count()
+                    .try_into()?;
+                available.push((guarantee, max_words.saturating_sub(count)));
+            }
+        }
+
+        let mut errors = vec![];
+        for (index, (parent, word)) in words.iter().enumerate() {
+            let guarantee = &word.metadata.guarantee.account;
+
+            // the replayed words are rejected
+            if nonces.contains(&word.metadata) {
+                errors.push(PutWordsError {
+                    index: index.try_into()?,
+                    error: nonce::replayed(),
+                });
+                continue;
+            }
+
+            // the words over the quota are rejected, and the rest are inserted
+            if let Some((_, available)) = available
+                .iter_mut()
+                .find(|(counted, _)| *counted == guarantee)
+            {
+                if *available == 0 {
+                    errors.push(PutWordsError {
+                        index: index.try_into()?,
+                        error: quota.exhausted_words(),
+                    });
+                    continue;
+                }
+                *available -= 1;
+            }
+            nonces.insert(&word.metadata, policy.retention_date(&word.metadata));
+
+            let key = GetWordKeyHash {
+                key: word.data.key,
+                kind: word.data.kind,
+            };
+
+            let id = store.next_id;
+            store.next_id += 1;
+
+            // insert the word record
+            store.records.push(WordRecord {
+                id,
+                parent: *parent,
+                word: *word,
+            });
+
+            // append the count of the word
+            WordCount::increment(&mut store.counts, id, None, parent, &key);
+
+            // append the count of the word of guarantee
+            WordCount::increment(
+                &mut store.counts_guarantees,
+                id,
+                Some(guarantee),
+                parent,
+                &key,
+            );
+        }
+        Ok(errors)
+    }
+
+    async fn delete_word_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        let mut words = self.words.write().await;
+        let words = &mut *words;
+
+        match guarantee {
+            Some(guarantee) => {
+                words.records.retain(|record| {
+                    &record.word.data.key.namespace != namespace
+                        || &record.word.metadata.guarantee.account != guarantee
+                });
+
+                // subtract the counts of the guarantee
+                let (owned, others): (Vec<_>, Vec<_>) =
+                    ::core::mem::take(&mut words.counts_guarantees)
+                        .into_iter()
+                        .partition(|count| {
+                            &count.word.key.namespace == namespace
+                                && count.guarantee.as_ref() == Some(guarantee)
+                        });
+                words.counts_guarantees = others;
+
+                for owned in owned {
+                    if let Some(count) = words
+                        .counts
+                        .iter_mut()
+                        .find(|count| count.parent == owned.parent && count.word == owned.word)
+                    {
+                        count.count = count.count.saturating_sub(owned.count);
+                    }
+                }
+                words.counts.retain(|count| count.count > 0);
+            }
+            None => {
+                words
+                    .records
+                    .retain(|record| &record.word.data.key.namespace != namespace);
+                words
+                    .counts
+                    .retain(|count| &count.word.key.namespace != namespace);
+                words
+                    .counts_guarantees
+                    .retain(|count| &count.word.key.namespace != namespace);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Checks whether the record comes after the cursor, the latest first.
+fn is_after(id: u64, cursor: Option<&SignedCursor>) -> bool {
+    match cursor {
+        Some(cursor) => id < cursor.data.id(),
+        None => true,
+    }
+}
+
+/// Returns `true` if the guarantee has been revoked after it was put.
+fn is_revoked(
+    revocations: &[(u64, Data<GuarantorSigned, AccountRef>)],
+    id: u64,
+    guarantee: &Data<GuarantorSigned, AccountRef>,
+) -> bool {
+    revocations.iter().any(|(revocation_id, revocation)| {
+        revocation.data == guarantee.metadata.guarantee.account
+            && revocation.metadata.guarantor.account == guarantee.metadata.guarantor.account
+            && *revocation_id > id
+    })
+}
+
+fn is_alive<T>(data: &Data<GuarantorSigned, T>, now: &NaiveDateTime) -> bool {
+    match data.metadata.expiration_date {
+        Some(expiration_date) => &expiration_date.naive_utc() >= now,
+        None => true,
+    }
+}
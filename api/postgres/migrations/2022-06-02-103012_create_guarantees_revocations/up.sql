@@ -0,0 +1,17 @@
+-- Your SQL goes here
+CREATE TABLE accounts_guarantees_revocations (
+  id SERIAL PRIMARY KEY,
+  -- METADATA BEGIN --
+  nonce NONCE NOT NULL,
+  guarantee ACCOUNT NOT NULL,
+  guarantor ACCOUNT NOT NULL,
+  guarantee_signature SIGNATURE NOT NULL UNIQUE,
+  guarantor_signature SIGNATURE NOT NULL UNIQUE,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  hash SHA256HASH NOT NULL,
+  -- METADATA END --
+  account ACCOUNT NOT NULL,
+  -- the latest guarantee revoked by this, in the order put into the server
+  revoked_id INTEGER NOT NULL
+);
\ No newline at end of file
@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE nonces (
+  id SERIAL PRIMARY KEY,
+  guarantee ACCOUNT NOT NULL,
+  nonce NONCE NOT NULL,
+  expiration_date TIMESTAMP,
+  UNIQUE (guarantee, nonce)
+);
+CREATE INDEX nonces_expiration_date ON nonces (expiration_date);
\ No newline at end of file
@@ -0,0 +1,19 @@
+-- Your SQL goes here
+CREATE TABLE accounts_guarantees_scopes (
+  id SERIAL PRIMARY KEY,
+  -- METADATA BEGIN --
+  nonce NONCE NOT NULL,
+  guarantee ACCOUNT NOT NULL,
+  guarantor ACCOUNT NOT NULL,
+  guarantee_signature SIGNATURE NOT NULL UNIQUE,
+  guarantor_signature SIGNATURE NOT NULL UNIQUE,
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  hash SHA256HASH NOT NULL,
+  -- METADATA END --
+  account ACCOUNT NOT NULL,
+  role VARCHAR(10) NOT NULL CHECK (role IN ('read_only', 'read_write', 'admin')),
+  -- the hashes in the signed order, or all the namespaces if NULL
+  namespaces TEXT[]
+);
+CREATE INDEX accounts_guarantees_scopes_account ON accounts_guarantees_scopes (account, guarantor);
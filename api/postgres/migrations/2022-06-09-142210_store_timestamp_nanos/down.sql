@@ -0,0 +1,16 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE accounts_guarantees
+  DROP COLUMN created_date_nanos,
+  DROP COLUMN expiration_date_nanos;
+ALTER TABLE accounts_guarantees_revocations
+  DROP COLUMN created_date_nanos,
+  DROP COLUMN expiration_date_nanos;
+ALTER TABLE accounts_guarantees_scopes
+  DROP COLUMN created_date_nanos,
+  DROP COLUMN expiration_date_nanos;
+ALTER TABLE dyn_paths
+  DROP COLUMN created_date_nanos,
+  DROP COLUMN expiration_date_nanos;
+ALTER TABLE words
+  DROP COLUMN created_date_nanos,
+  DROP COLUMN expiration_date_nanos;
\ No newline at end of file
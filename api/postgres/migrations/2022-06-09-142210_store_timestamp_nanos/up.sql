@@ -0,0 +1,18 @@
+-- Your SQL goes here
+-- TIMESTAMP keeps microseconds, so the signed nanoseconds are stored aside
+-- (NULL for the records put before, which are read back as they are)
+ALTER TABLE accounts_guarantees
+  ADD COLUMN created_date_nanos INTEGER,
+  ADD COLUMN expiration_date_nanos INTEGER;
+ALTER TABLE accounts_guarantees_revocations
+  ADD COLUMN created_date_nanos INTEGER,
+  ADD COLUMN expiration_date_nanos INTEGER;
+ALTER TABLE accounts_guarantees_scopes
+  ADD COLUMN created_date_nanos INTEGER,
+  ADD COLUMN expiration_date_nanos INTEGER;
+ALTER TABLE dyn_paths
+  ADD COLUMN created_date_nanos INTEGER,
+  ADD COLUMN expiration_date_nanos INTEGER;
+ALTER TABLE words
+  ADD COLUMN created_date_nanos INTEGER,
+  ADD COLUMN expiration_date_nanos INTEGER;
\ No newline at end of file
@@ -1,6 +1,8 @@
 #[macro_use]
 extern crate diesel;
 
-pub mod client;
 mod models;
 mod schema;
+mod storage;
+
+pub use self::storage::PostgresStorage;
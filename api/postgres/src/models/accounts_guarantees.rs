@@ -13,6 +13,8 @@ pub struct AccountsGuarantee {
     pub expiration_date: Option<NaiveDateTime>,
     pub hash: String,
     // -- METADATA END --
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -28,4 +30,84 @@ pub struct NewAccountsGuarantee {
     pub expiration_date: Option<NaiveDateTime>,
     pub hash: String,
     // -- METADATA END --
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
+}
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuaranteesRevocation {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub revoked_id: i32,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees_revocations)]
+pub struct NewAccountsGuaranteesRevocation {
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub revoked_id: i32,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
+}
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuaranteesScope {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub role: String,
+    pub namespaces: Option<Vec<String>>,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees_scopes)]
+pub struct NewAccountsGuaranteesScope {
+    // -- METADATA BEGIN --
+    pub nonce: Uuid,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub role: String,
+    pub namespaces: Option<Vec<String>>,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
@@ -18,6 +18,8 @@ pub struct DynPath {
     pub word: String,
     pub path: String,
     pub len: i64,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -38,4 +40,6 @@ pub struct NewDynPath {
     pub word: String,
     pub path: String,
     pub len: i64,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
@@ -1,3 +1,4 @@
 pub mod accounts_guarantees;
 pub mod dyn_paths;
+pub mod nonces;
 pub mod words;
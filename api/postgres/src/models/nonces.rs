@@ -0,0 +1,9 @@
+use ipis::core::{chrono::NaiveDateTime, uuid::Uuid};
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::nonces)]
+pub struct NewNonce {
+    pub guarantee: String,
+    pub nonce: Uuid,
+    pub expiration_date: Option<NaiveDateTime>,
+}
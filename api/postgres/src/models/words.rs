@@ -21,6 +21,8 @@ pub struct Word {
     pub relpath: bool,
     pub path: String,
     pub len: i64,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
 
 #[derive(Insertable)]
@@ -44,6 +46,8 @@ pub struct NewWord {
     pub relpath: bool,
     pub path: String,
     pub len: i64,
+    pub created_date_nanos: Option<i32>,
+    pub expiration_date_nanos: Option<i32>,
 }
 
 #[derive(Debug, Queryable)]
@@ -11,6 +11,45 @@ diesel::table! {
         created_date -> Timestamp,
         expiration_date -> Nullable<Timestamp>,
         hash -> Varchar,
+        created_date_nanos -> Nullable<Int4>,
+        expiration_date_nanos -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    accounts_guarantees_revocations (id) {
+        id -> Int4,
+        nonce -> Uuid,
+        guarantee -> Varchar,
+        guarantor -> Varchar,
+        guarantee_signature -> Varchar,
+        guarantor_signature -> Varchar,
+        created_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+        hash -> Varchar,
+        account -> Varchar,
+        revoked_id -> Int4,
+        created_date_nanos -> Nullable<Int4>,
+        expiration_date_nanos -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    accounts_guarantees_scopes (id) {
+        id -> Int4,
+        nonce -> Uuid,
+        guarantee -> Varchar,
+        guarantor -> Varchar,
+        guarantee_signature -> Varchar,
+        guarantor_signature -> Varchar,
+        created_date -> Timestamp,
+        expiration_date -> Nullable<Timestamp>,
+        hash -> Varchar,
+        account -> Varchar,
+        role -> Varchar,
+        namespaces -> Nullable<Array<Text>>,
+        created_date_nanos -> Nullable<Int4>,
+        expiration_date_nanos -> Nullable<Int4>,
     }
 }
 
@@ -30,6 +69,17 @@ diesel::table! {
         word -> Varchar,
         path -> Varchar,
         len -> Int8,
+        created_date_nanos -> Nullable<Int4>,
+        expiration_date_nanos -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    nonces (id) {
+        id -> Int4,
+        guarantee -> Varchar,
+        nonce -> Uuid,
+        expiration_date -> Nullable<Timestamp>,
     }
 }
 
@@ -52,6 +102,8 @@ diesel::table! {
         relpath -> Bool,
         path -> Varchar,
         len -> Int8,
+        created_date_nanos -> Nullable<Int4>,
+        expiration_date_nanos -> Nullable<Int4>,
     }
 }
 
@@ -82,7 +134,10 @@ diesel::table! {
 
 diesel::allow_tables_to_appear_in_same_query!(
     accounts_guarantees,
+    accounts_guarantees_revocations,
+    accounts_guarantees_scopes,
     dyn_paths,
+    nonces,
     words,
     words_counts,
     words_counts_guarantees,
@@ -0,0 +1,1072 @@
+use std::sync::Mutex;
+
+use bytecheck::CheckBytes;
+use ipdis_api_common::{
+    nonce,
+    policy::RequestPolicy,
+    quota::{count_by_guarantee, QuotaPolicy, Usage},
+    storage::IpdisStorage,
+};
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWordKeyHash, GetWords, GetWordsCounts, GetWordsCountsOutput,
+    GetWordsParent, GuaranteeScope, NamespaceStats, Page, PutWordsError,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{anyhow, bail, Error, Result},
+        chrono::{NaiveDateTime, Utc},
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
+    env,
+    path::{DynPath, Path},
+    word::WordHash,
+};
+use rkyv::{
+    de::deserializers::SharedDeserializeMap, ser::serializers::AllocSerializer,
+    validation::validators::DefaultValidator, AlignedVec, Archive, Deserialize, Serialize,
+};
+use sled::{
+    transaction::{
+        ConflictableTransactionError, ConflictableTransactionResult, TransactionError,
+        TransactionalTree,
+    },
+    Db, Transactional, Tree,
+};
+
+/// An embedded key-value storage which keeps the records as archived bytes.
+///
+/// Each key is composed of the archived components (with their length prefixes)
+/// followed by a big-endian id, so that the latest record comes last in a prefix scan.
+pub struct SledStorage {
+    db: Db,
+    guarantees: Tree,
+    guarantees_revocations: Tree,
+    guarantees_scopes: Tree,
+    nonces: Tree,
+    /// the keys of the nonces, prefixed by their expiration dates
+    nonces_expirations: Tree,
+    dyn_paths: Tree,
+    words: Tree,
+    words_parents: Tree,
+    words_counts: Tree,
+    words_counts_parents: Tree,
+    words_counts_guarantees: Tree,
+    words_counts_guarantees_parents: Tree,
+    /// serializes the puts counted against the quotas, as the transactions cannot scan the trees
+    quota_lock: Mutex<()>,
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+struct WordRecord {
+    parent: Hash,
+    word: Data<GuarantorSigned, WordHash>,
+}
+
+#[derive(Archive, Serialize, Deserialize)]
+#[archive_attr(derive(CheckBytes))]
+struct WordCountRecord {
+    /// the id of the word which has created the count
+    id: u64,
+    count: GetWordsCountsOutput,
+}
+
+impl SledStorage {
+    fn count_words(&self, guarantee: &AccountRef) -> Result<u64> {
+        let prefix = Key::default().with(guarantee)?;
+
+        let mut words = 0;
+        for entry in self.words_counts_guarantees.scan_prefix(&prefix) {
+            let (_, value) = entry?;
+            let record: WordCountRecord = decode(&value)?;
+
+            words += u64::from(record.count.count);
+        }
+        Ok(words)
+    }
+
+    fn count_dyn_paths(&self, guarantee: &AccountRef) -> Result<u64> {
+        let prefix = Key::default().with(guarantee)?;
+
+        let mut dyn_paths = 0;
+        for entry in self.dyn_paths.scan_prefix(&prefix) {
+            entry?;
+            dyn_paths += 1;
+        }
+        Ok(dyn_paths)
+    }
+
+    /// Returns the id of the latest revocation, which revokes the guarantees put before it.
+    ///
+    /// The ids are generated by the database, so they follow the order put into the server.
+    fn get_guarantee_revoked_id(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<u64>> {
+        let prefix = Key::default().with(guarantee)?.with(guarantor)?;
+
+        match self.guarantees_revocations.scan_prefix(prefix).next_back() {
+            Some(entry) => {
+                let (key, _) = entry?;
+                id_of(&key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[async_trait]
+impl IpdisStorage for SledStorage {
+    async fn try_infer() -> Result<Self> {
+        let database_url: String = env::infer("DATABASE_URL")?;
+
+        let db = ::sled::open(&database_url)
+            .map_err(|e| anyhow!("Error connecting to {database_url}: {e}"))?;
+
+        Ok(Self {
+            guarantees: db.open_tree("accounts_guarantees")?,
+            guarantees_revocations: db.open_tree("accounts_guarantees_revocations")?,
+            guarantees_scopes: db.open_tree("accounts_guarantees_scopes")?,
+            nonces: db.open_tree("nonces")?,
+            nonces_expirations: db.open_tree("nonces_expirations")?,
+            dyn_paths: db.open_tree("dyn_paths")?,
+            words: db.open_tree("words")?,
+            words_parents: db.open_tree("words_parents")?,
+            words_counts: db.open_tree("words_counts")?,
+            words_counts_parents: db.open_tree("words_counts_parents")?,
+            words_counts_guarantees: db.open_tree("words_counts_guarantees")?,
+            words_counts_guarantees_parents: db.open_tree("words_counts_guarantees_parents")?,
+            quota_lock: Mutex::default(),
+            db,
+        })
+    }
+
+    async fn contains_guarantee(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        let now = Utc::now().naive_utc();
+        let revoked_id = self.get_guarantee_revoked_id(guarantee, guarantor)?;
+        let prefix = Key::default().with(guarantee)?.with(guarantor)?;
+
+        for entry in self.guarantees.scan_prefix(prefix) {
+            let (key, value) = entry?;
+            let record: Data<GuarantorSigned, AccountRef> = decode(&value)?;
+
+            let is_revoked = match revoked_id {
+                Some(revoked_id) => id_of(&key)? < revoked_id,
+                None => false,
+            };
+            if is_alive(&record, &now) && !is_revoked {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    async fn put_guarantee(&self, guarantee: &Data<GuarantorSigned, AccountRef>) -> Result<()> {
+        let key = Key::default()
+            .with(&guarantee.metadata.guarantee.account)?
+            .with(&guarantee.metadata.guarantor.account)?
+            .with_id(self.db.generate_id()?);
+
+        self.guarantees
+            .insert(key, encode(guarantee)?.as_slice())
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    async fn get_guarantee_many(
+        &self,
+        guarantor: &AccountRef,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        let now = Utc::now().naive_utc();
+        let prefix = match &query.guarantee {
+            Some(guarantee) => Key::default().with(guarantee)?.with(guarantor)?,
+            None => Key::default(),
+        };
+
+        let mut records = vec![];
+        for entry in self.guarantees.scan_prefix(prefix) {
+            let (key, value) = entry?;
+            let record: Data<GuarantorSigned, AccountRef> = decode(&value)?;
+
+            if &record.metadata.guarantor.account != guarantor || !is_alive(&record, &now) {
+                continue;
+            }
+
+            let is_revoked = match self
+                .get_guarantee_revoked_id(&record.metadata.guarantee.account, guarantor)?
+            {
+                Some(revoked_id) => id_of(&key)? < revoked_id,
+                None => false,
+            };
+            if !is_revoked {
+                records.push(record);
+            }
+        }
+
+        // the latest one comes first
+        records
+            .sort_by_key(|record| ::core::cmp::Reverse(record.metadata.created_date.naive_utc()));
+
+        Ok(records
+            .into_iter()
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .collect())
+    }
+
+    async fn delete_guarantee_all(&self, guarantee: &AccountRef) -> Result<()> {
+        let prefix = Key::default().with(guarantee)?;
+
+        for entry in self.guarantees.scan_prefix(&prefix) {
+            let (key, _) = entry?;
+            self.guarantees.remove(key)?;
+        }
+
+        // the scopes are given to the guarantees, so they are deleted together
+        for entry in self.guarantees_scopes.scan_prefix(&prefix) {
+            let (key, _) = entry?;
+            self.guarantees_scopes.remove(key)?;
+        }
+        Ok(())
+    }
+
+    async fn get_guarantee_revocation_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, AccountRef>>> {
+        let prefix = Key::default().with(guarantee)?.with(guarantor)?;
+
+        // the latest one is the last one put, as the keys end with the generated ids
+        match self.guarantees_revocations.scan_prefix(prefix).next_back() {
+            Some(entry) => {
+                let (_, value) = entry?;
+                decode(&value).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put_guarantee_revocation(
+        &self,
+        revocation: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()> {
+        let key = Key::default()
+            .with(&revocation.data)?
+            .with(&revocation.metadata.guarantor.account)?
+            .with_id(self.db.generate_id()?);
+
+        self.guarantees_revocations
+            .insert(key, encode(revocation)?.as_slice())
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    async fn get_guarantee_scope_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, GuaranteeScope>>> {
+        let prefix = Key::default().with(guarantee)?.with(guarantor)?;
+
+        // the latest one is the last one put, as the keys end with the generated ids
+        match self.guarantees_scopes.scan_prefix(prefix).next_back() {
+            Some(entry) => {
+                let (_, value) = entry?;
+                decode(&value).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn put_guarantee_scope(
+        &self,
+        scope: &Data<GuarantorSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        let key = Key::default()
+            .with(&scope.data.account)?
+            .with(&scope.metadata.guarantor.account)?
+            .with_id(self.db.generate_id()?);
+
+        self.guarantees_scopes
+            .insert(key, encode(scope)?.as_slice())
+            .map(|_| ())
+            .map_err(Into::into)
+    }
+
+    async fn put_nonce(
+        &self,
+        request: &GuaranteeSigned,
+        retention_date: NaiveDateTime,
+    ) -> Result<bool> {
+        let keys = NonceKeys::try_new(request, &retention_date)?;
+
+        (&self.nonces, &self.nonces_expirations)
+            .transaction(|(nonces, nonces_expirations)| {
+                insert_nonce(nonces, nonces_expirations, &keys)
+            })
+            .map_err(unwrap_transaction_error)
+    }
+
+    async fn sweep_nonces(&self) -> Result<()> {
+        let now = Key::default().with_id(timestamp_of(&Utc::now().naive_utc()));
+        for entry in self.nonces_expirations.range(..now) {
+            let (key, _) = entry?;
+
+            self.nonces.remove(&key[8..])?;
+            self.nonces_expirations.remove(key)?;
+        }
+        Ok(())
+    }
+
+    async fn get_dyn_path(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let now = Utc::now().naive_utc();
+        let prefix = Key::default()
+            .with(guarantee)?
+            .with(guarantor)?
+            .with(&path.namespace)?
+            .with(&path.kind)?
+            .with(&path.word)?;
+
+        let mut latest: Option<Data<GuarantorSigned, DynPath<Path>>> = None;
+        for entry in self.dyn_paths.scan_prefix(prefix) {
+            let (_, value) = entry?;
+            let record: Data<GuarantorSigned, DynPath<Path>> = decode(&value)?;
+
+            // the latest one wins
+            let is_latest = match &latest {
+                Some(latest) => {
+                    latest.metadata.created_date.naive_utc()
+                        <= record.metadata.created_date.naive_utc()
+                }
+                None => true,
+            };
+            if is_alive(&record, &now) && is_latest {
+                latest = Some(record);
+            }
+        }
+        Ok(latest)
+    }
+
+    async fn get_dyn_path_at(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let timestamp = timestamp.naive_utc();
+        let prefix = Key::default()
+            .with(guarantee)?
+            .with(guarantor)?
+            .with(&path.namespace)?
+            .with(&path.kind)?
+            .with(&path.word)?;
+
+        let mut latest: Option<Data<GuarantorSigned, DynPath<Path>>> = None;
+        for entry in self.dyn_paths.scan_prefix(prefix) {
+            let (_, value) = entry?;
+            let record: Data<GuarantorSigned, DynPath<Path>> = decode(&value)?;
+            let created_date = record.metadata.created_date.naive_utc();
+
+            // the latest one before the timestamp wins
+            let is_latest = match &latest {
+                Some(latest) => latest.metadata.created_date.naive_utc() <= created_date,
+                None => true,
+            };
+            if created_date <= timestamp && is_alive(&record, &timestamp) && is_latest {
+                latest = Some(record);
+            }
+        }
+        Ok(latest)
+    }
+
+    async fn get_dyn_path_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>>> {
+        let prefix = Key::default()
+            .with(guarantee)?
+            .with(guarantor)?
+            .with(&query.path.namespace)?
+            .with(&query.path.kind)?
+            .with(&query.path.word)?;
+
+        // continue after the last seen record
+        let entries = match &query.cursor {
+            Some(cursor) => self
+                .dyn_paths
+                .range(prefix.clone()..prefix.with_id(cursor.data.id())),
+            None => self.dyn_paths.scan_prefix(prefix),
+        };
+
+        let mut records = vec![];
+        let mut index = 0;
+        // the latest one comes first
+        for entry in entries.rev() {
+            if index >= query.end_index {
+                break;
+            }
+
+            let (key, value) = entry?;
+            let record: Data<GuarantorSigned, DynPath<Path>> = decode(&value)?;
+            let created_date = record.metadata.created_date.naive_utc();
+
+            let is_since = match &query.since {
+                Some(since) => created_date >= since.naive_utc(),
+                None => true,
+            };
+            let is_until = match &query.until {
+                Some(until) => created_date <= until.naive_utc(),
+                None => true,
+            };
+            if is_since && is_until {
+                if index >= query.start_index {
+                    records.push((id_of(&key)?, record));
+                }
+                index += 1;
+            }
+        }
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn put_dyn_path(
+        &self,
+        path: &Data<GuarantorSigned, DynPath<Path>>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()> {
+        // count under the lock, so that the concurrent puts cannot exceed the quota
+        let _quota_lock = match quota.max_dyn_paths {
+            Some(_) => {
+                let quota_lock = self.quota_lock.lock().unwrap();
+                let usage = Usage {
+                    words: 0,
+                    dyn_paths: self.count_dyn_paths(&path.metadata.guarantee.account)?,
+                };
+                quota.ensure_available(&usage, 0, 1)?;
+                Some(quota_lock)
+            }
+            None => None,
+        };
+
+        let key = Key::default()
+            .with(&path.metadata.guarantee.account)?
+            .with(&path.metadata.guarantor.account)?
+            .with(&path.data.namespace)?
+            .with(&path.data.kind)?
+            .with(&path.data.word)?
+            .with_id(self.db.generate_id()?);
+        let value = encode(path)?;
+        let keys = NonceKeys::try_new(&path.metadata, &policy.retention_date(&path.metadata))?;
+
+        // record the nonce along with the path
+        let inserted = (&self.dyn_paths, &self.nonces, &self.nonces_expirations)
+            .transaction(|(dyn_paths, nonces, nonces_expirations)| {
+                if !insert_nonce(nonces, nonces_expirations, &keys)? {
+                    return Ok(false);
+                }
+                dyn_paths.insert(key.as_ref(), value.as_slice())?;
+                Ok(true)
+            })
+            .map_err(unwrap_transaction_error)?;
+
+        if !inserted {
+            bail!(nonce::replayed())
+        }
+        Ok(())
+    }
+
+    async fn delete_dyn_path_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        let prefix = match guarantee {
+            Some(guarantee) => Key::default().with(guarantee)?,
+            None => Key::default(),
+        };
+
+        for entry in self.dyn_paths.scan_prefix(prefix) {
+            let (key, value) = entry?;
+            let record: Data<GuarantorSigned, DynPath<Path>> = decode(&value)?;
+
+            if &record.data.namespace == namespace {
+                self.dyn_paths.remove(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn get_word_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>>> {
+        let now = Utc::now().naive_utc();
+        let prefix = Key::default()
+            .with(guarantee)?
+            .with(&query.word.namespace)?
+            .with(&query.word.text.lang)?
+            .with(&query.word.text.msg)?;
+
+        let tree = match query.parent {
+            GetWordsParent::None => &self.words,
+            GetWordsParent::Duplicated => &self.words_parents,
+        };
+
+        // continue after the last seen record
+        let entries = match &query.cursor {
+            Some(cursor) => tree.range(prefix.clone()..prefix.with_id(cursor.data.id())),
+            None => tree.scan_prefix(prefix),
+        };
+
+        let mut records = vec![];
+        let mut index = 0;
+        // the latest one comes first
+        for entry in entries.rev() {
+            if index >= query.end_index {
+                break;
+            }
+
+            let (key, value) = entry?;
+            let record: WordRecord = decode(&value)?;
+            let word = record.word;
+
+            if &word.metadata.guarantor.account == guarantor && is_alive(&word, &now) {
+                if index >= query.start_index {
+                    records.push((id_of(&key)?, word));
+                }
+                index += 1;
+            }
+        }
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn get_word_count_many(
+        &self,
+        guarantee: &AccountRef,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        let (tree, prefix) = match (query.owned, query.parent) {
+            (true, true) => (
+                &self.words_counts_guarantees_parents,
+                Key::default().with(guarantee)?,
+            ),
+            (true, false) => (
+                &self.words_counts_guarantees,
+                Key::default().with(guarantee)?,
+            ),
+            (false, true) => (&self.words_counts_parents, Key::default()),
+            (false, false) => (&self.words_counts, Key::default()),
+        };
+        let prefix = prefix
+            .with(&query.word.namespace)?
+            .with(&query.word.text.lang)?
+            .with(&query.word.text.msg)?;
+
+        let mut records = tree
+            .scan_prefix(prefix)
+            .map(|entry| {
+                let (_, value) = entry?;
+                decode::<WordCountRecord>(&value)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the latest one comes first
+        records.sort_by_key(|record| ::core::cmp::Reverse(record.id));
+
+        // continue after the last seen record
+        let records = records
+            .into_iter()
+            .filter(|record| match &query.cursor {
+                Some(cursor) => record.id < cursor.data.id(),
+                None => true,
+            })
+            .skip(query.start_index as usize)
+            .take((query.end_index - query.start_index) as usize)
+            .map(|record| (record.id, record.count))
+            .collect();
+        Ok(Page::with_ids(records, query.end_index - query.start_index))
+    }
+
+    async fn get_namespace_stats(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        let mut stats = NamespaceStats::default();
+
+        // the same words are adjacent, as the keys begin with the namespace and the word
+        let mut last_word = None;
+        for entry in self
+            .words_counts
+            .scan_prefix(Key::default().with(namespace)?)
+        {
+            let (_, value) = entry?;
+            let record: WordCountRecord = decode(&value)?;
+
+            stats.total_words += u64::from(record.count.count);
+
+            let word = record.count.word.key.text;
+            if last_word.as_ref() != Some(&word) {
+                stats.distinct_words += 1;
+                last_word = Some(word);
+            }
+        }
+
+        // the same guarantees are adjacent, as the keys begin with the guarantee
+        let mut last_guarantee = None;
+        for entry in self.words_counts_guarantees.iter() {
+            let (key, value) = entry?;
+            let record: WordCountRecord = decode(&value)?;
+
+            if &record.count.word.key.namespace != namespace {
+                continue;
+            }
+
+            let guarantee = first_of(&key)?.to_vec();
+            if last_guarantee.as_ref() != Some(&guarantee) {
+                stats.distinct_guarantees += 1;
+                last_guarantee = Some(guarantee);
+            }
+        }
+        Ok(stats)
+    }
+
+    async fn get_usage(&self, guarantee: &AccountRef) -> Result<Usage> {
+        Ok(Usage {
+            words: self.count_words(guarantee)?,
+            dyn_paths: self.count_dyn_paths(guarantee)?,
+        })
+    }
+
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuarantorSigned, WordHash>)],
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<Vec<PutWordsError>> {
+        // count under the lock, so that the concurrent puts cannot exceed the quota
+        let mut available = vec![];
+        let _quota_lock = match quota.max_words {
+            Some(max_words) => {
+                let quota_lock = self.quota_lock.lock().unwrap();
+                let guarantees = words
+                    .iter()
+                    .map(|(_, word)| &word.metadata.guarantee.account);
+                for (guarantee, _) in count_by_guarantee(guarantees) {
+                    let count = self.count_words(guarantee)?;
+                    available.push((guarantee, max_words.saturating_sub(count)));
+                }
+                Some(quota_lock)
+            }
+            None => None,
+        };
+
+        let mut entries = vec![];
+        for (index, (parent, word)) in words.iter().enumerate() {
+            let keys = NonceKeys::try_new(&word.metadata, &policy.retention_date(&word.metadata))?;
+            let entry = WordEntry::try_new(self.db.generate_id()?, parent, word)?;
+            entries.push((index, &word.metadata.guarantee.account, keys, entry));
+        }
+
+        let rejected = (
+            &self.nonces,
+            &self.nonces_expirations,
+            &self.words,
+            &self.words_parents,
+            &self.words_counts,
+            &self.words_counts_parents,
+            &self.words_counts_guarantees,
+            &self.words_counts_guarantees_parents,
+        )
+            .transaction(
+                |(
+                    nonces,
+                    nonces_expirations,
+                    words,
+                    words_parents,
+                    words_counts,
+                    words_counts_parents,
+                    words_counts_guarantees,
+                    words_counts_guarantees_parents,
+                )| {
+                    let mut available = available.clone();
+                    let mut rejected = vec![];
+                    for (index, guarantee, keys, entry) in &entries {
+                        // the replayed words are rejected
+                        if nonces.get(&keys.key)?.is_some() {
+                            rejected.push((*index, nonce::replayed()));
+                            continue;
+                        }
+
+                        // the words over the quota are rejected, and the rest are inserted
+                        if let Some((_, available)) = available
+                            .iter_mut()
+                            .find(|(counted, _)| counted == guarantee)
+                        {
+                            if *available == 0 {
+                                rejected.push((*index, quota.exhausted_words()));
+                                continue;
+                            }
+                            *available -= 1;
+                        }
+
+                        // record the nonce along with the word
+                        insert_nonce(nonces, nonces_expirations, keys)?;
+
+                        let id = entry.id;
+                        let count = &entry.count;
+
+                        // insert the word record
+                        words.insert(entry.key_word.as_ref(), entry.record.as_slice())?;
+                        words_parents.insert(entry.key_parent.as_ref(), entry.record.as_slice())?;
+
+                        // append the counts
+                        increment(words_counts, &entry.key_count_word, id, count)?;
+                        increment(words_counts_parents, &entry.key_count_parent, id, count)?;
+                        increment(
+                            words_counts_guarantees,
+                            &entry.key_count_guarantee_word,
+                            id,
+                            count,
+                        )?;
+                        increment(
+                            words_counts_guarantees_parents,
+                            &entry.key_count_guarantee_parent,
+                            id,
+                            count,
+                        )?;
+                    }
+                    Ok(rejected)
+                },
+            )
+            .map_err(unwrap_transaction_error)?;
+
+        rejected
+            .into_iter()
+            .map(|(index, error)| {
+                Ok(PutWordsError {
+                    index: index.try_into()?,
+                    error,
+                })
+            })
+            .collect()
+    }
+
+    async fn delete_word_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        if let Some(guarantee) = guarantee {
+            let prefix_guarantee = Key::default().with(guarantee)?;
+            let prefix = prefix_guarantee.clone().with(namespace)?;
+
+            for tree in [&self.words, &self.words_parents] {
+                for entry in tree.scan_prefix(&prefix) {
+                    let (key, _) = entry?;
+                    tree.remove(key)?;
+                }
+            }
+
+            // subtract the counts of the guarantee
+            for (tree_guarantee, tree) in [
+                (&self.words_counts_guarantees, &self.words_counts),
+                (
+                    &self.words_counts_guarantees_parents,
+                    &self.words_counts_parents,
+                ),
+            ] {
+                for entry in tree_guarantee.scan_prefix(&prefix) {
+                    let (key, value) = entry?;
+                    let owned: WordCountRecord = decode(&value)?;
+
+                    decrement(
+                        tree,
+                        &key[prefix_guarantee.as_ref().len()..],
+                        owned.count.count,
+                    )?;
+                    tree_guarantee.remove(key)?;
+                }
+            }
+            return Ok(());
+        }
+
+        for tree in [&self.words, &self.words_parents] {
+            for entry in tree.iter() {
+                let (key, value) = entry?;
+                let record: WordRecord = decode(&value)?;
+
+                if &record.word.data.key.namespace == namespace {
+                    tree.remove(key)?;
+                }
+            }
+        }
+
+        for tree in [
+            &self.words_counts,
+            &self.words_counts_parents,
+            &self.words_counts_guarantees,
+            &self.words_counts_guarantees_parents,
+        ] {
+            for entry in tree.iter() {
+                let (key, value) = entry?;
+                let record: WordCountRecord = decode(&value)?;
+
+                if &record.count.word.key.namespace == namespace {
+                    tree.remove(key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A word record and its keys, prepared before the transaction.
+struct WordEntry {
+    id: u64,
+    count: GetWordKeyHash,
+    record: AlignedVec,
+    key_word: Key,
+    key_parent: Key,
+    key_count_word: Key,
+    key_count_parent: Key,
+    key_count_guarantee_word: Key,
+    key_count_guarantee_parent: Key,
+}
+
+impl WordEntry {
+    fn try_new(id: u64, parent: &Hash, word: &Data<GuarantorSigned, WordHash>) -> Result<Self> {
+        let guarantee = &word.metadata.guarantee.account;
+        let namespace = &word.data.key.namespace;
+        let lang = &word.data.key.text.lang;
+        let msg = &word.data.key.text.msg;
+        let kind = &word.data.kind;
+
+        let count = GetWordKeyHash {
+            key: word.data.key,
+            kind: *kind,
+        };
+        let record = encode(&WordRecord {
+            parent: *parent,
+            word: *word,
+        })?;
+
+        let key = Key::default()
+            .with(guarantee)?
+            .with(namespace)?
+            .with(lang)?;
+        let key_word = key.clone().with(msg)?.with_id(id);
+        let key_parent = key.with(parent)?.with_id(id);
+
+        let key_count = Key::default().with(namespace)?.with(lang)?;
+        let key_count_word = key_count.clone().with(msg)?.with(parent)?.with(kind)?;
+        let key_count_parent = key_count.with(parent)?.with(msg)?.with(kind)?;
+
+        let key_count_guarantee = Key::default().with(guarantee)?;
+        let key_count_guarantee_word = key_count_guarantee.clone().with_key(&key_count_word);
+        let key_count_guarantee_parent = key_count_guarantee.with_key(&key_count_parent);
+
+        Ok(Self {
+            id,
+            count,
+            record,
+            key_word,
+            key_parent,
+            key_count_word,
+            key_count_parent,
+            key_count_guarantee_word,
+            key_count_guarantee_parent,
+        })
+    }
+}
+
+/// The key of a nonce, and the one of its retention date.
+struct NonceKeys {
+    key: Key,
+    key_expiration: Key,
+}
+
+impl NonceKeys {
+    fn try_new(request: &GuaranteeSigned, retention_date: &NaiveDateTime) -> Result<Self> {
+        let key = Key::default()
+            .with(&request.guarantee.account)?
+            .with(&request.data.nonce)?;
+        let key_expiration = Key::default()
+            .with_id(timestamp_of(retention_date))
+            .with_key(&key);
+
+        Ok(Self {
+            key,
+            key_expiration,
+        })
+    }
+}
+
+/// A composite key of the length-prefixed archived components.
+#[derive(Clone, Default)]
+struct Key(Vec<u8>);
+
+impl AsRef<[u8]> for Key {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Key {
+    fn with<T>(mut self, component: &T) -> Result<Self>
+    where
+        T: Serialize<AllocSerializer<64>>,
+    {
+        let component = encode(component)?;
+        self.0
+            .extend_from_slice(&u16::try_from(component.len())?.to_be_bytes());
+        self.0.extend_from_slice(&component);
+        Ok(self)
+    }
+
+    fn with_id(mut self, id: u64) -> Self {
+        self.0.extend_from_slice(&id.to_be_bytes());
+        self
+    }
+
+    fn with_key(mut self, key: &Self) -> Self {
+        self.0.extend_from_slice(&key.0);
+        self
+    }
+}
+
+/// Inserts the nonce only if it is not recorded yet.
+fn insert_nonce(
+    nonces: &TransactionalTree,
+    nonces_expirations: &TransactionalTree,
+    keys: &NonceKeys,
+) -> ConflictableTransactionResult<bool, Error> {
+    if nonces.get(&keys.key)?.is_some() {
+        return Ok(false);
+    }
+
+    nonces.insert(keys.key.as_ref(), &[] as &[u8])?;
+    nonces_expirations.insert(keys.key_expiration.as_ref(), &[] as &[u8])?;
+    Ok(true)
+}
+
+fn increment(
+    tree: &TransactionalTree,
+    key: &Key,
+    id: u64,
+    word: &GetWordKeyHash,
+) -> ConflictableTransactionResult<(), Error> {
+    let record = match tree.get(key)? {
+        // old word => append the count
+        Some(value) => {
+            let mut record =
+                decode::<WordCountRecord>(&value).map_err(ConflictableTransactionError::Abort)?;
+            record.count.count += 1;
+            record
+        }
+        // new word => insert the word record
+        None => WordCountRecord {
+            id,
+            count: GetWordsCountsOutput {
+                word: *word,
+                count: 1,
+            },
+        },
+    };
+
+    let value = encode(&record).map_err(ConflictableTransactionError::Abort)?;
+    tree.insert(key.as_ref(), value.as_slice())?;
+    Ok(())
+}
+
+fn decrement(tree: &Tree, key: &[u8], count: u32) -> Result<()> {
+    tree.transaction(|tree| {
+        if let Some(value) = tree.get(key)? {
+            let mut record: WordCountRecord =
+                decode(&value).map_err(ConflictableTransactionError::Abort)?;
+
+            record.count.count = record.count.count.saturating_sub(count);
+            if record.count.count > 0 {
+                let value = encode(&record).map_err(ConflictableTransactionError::Abort)?;
+                tree.insert(key, value.as_slice())?;
+            } else {
+                tree.remove(key)?;
+            }
+        }
+        Ok(())
+    })
+    .map_err(unwrap_transaction_error)
+}
+
+/// Converts the date into an id, which is ordered as the date.
+fn timestamp_of(date: &NaiveDateTime) -> u64 {
+    date.timestamp().try_into().unwrap_or_default()
+}
+
+/// Extracts the archived first component of the key.
+fn first_of(key: &[u8]) -> Result<&[u8]> {
+    key.get(..2)
+        .and_then(|len| len.try_into().ok())
+        .map(|len| 2 + u16::from_be_bytes(len) as usize)
+        .and_then(|end| key.get(2..end))
+        .ok_or_else(|| anyhow!("malformed key"))
+}
+
+/// Extracts the id at the end of the key.
+fn id_of(key: &[u8]) -> Result<u64> {
+    key.len()
+        .checked_sub(8)
+        .and_then(|start| key[start..].try_into().ok())
+        .map(u64::from_be_bytes)
+        .ok_or_else(|| anyhow!("malformed key"))
+}
+
+fn unwrap_transaction_error(error: TransactionError<Error>) -> Error {
+    match error {
+        TransactionError::Abort(error) => error,
+        TransactionError::Storage(error) => error.into(),
+    }
+}
+
+fn encode<T>(value: &T) -> Result<AlignedVec>
+where
+    T: Serialize<AllocSerializer<64>>,
+{
+    ::rkyv::to_bytes::<_, 64>(value).map_err(|_| anyhow!("failed to archive the record"))
+}
+
+fn decode<T>(bytes: &[u8]) -> Result<T>
+where
+    T: Archive,
+    <T as Archive>::Archived:
+        for<'a> CheckBytes<DefaultValidator<'a>> + Deserialize<T, SharedDeserializeMap>,
+{
+    // the archived bytes should be aligned
+    let mut buf = AlignedVec::with_capacity(bytes.len());
+    buf.extend_from_slice(bytes);
+
+    ::rkyv::from_bytes(&buf).map_err(|_| anyhow!("malformed record"))
+}
+
+fn is_alive<T>(data: &Data<GuarantorSigned, T>, now: &NaiveDateTime) -> bool {
+    match data.metadata.expiration_date {
+        Some(expiration_date) => &expiration_date.naive_utc() >= now,
+        None => true,
+    }
+}
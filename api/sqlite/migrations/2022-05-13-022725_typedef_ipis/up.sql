@@ -0,0 +1,11 @@
+-- Your SQL goes here
+-- SQLite does not support domains, so each column inlines its constraints:
+-- NONCE: UUID (hyphenated) = 36 bytes
+--   CHECK (LENGTH(value) = 36)
+-- ACCOUNT: ED25519 PublicKey - base58 = 32 bytes
+--   CHECK (LENGTH(value) BETWEEN 43 AND 44)
+-- SIGNATURE: ED25519 Signature - base58 = 64 bytes
+--   CHECK (LENGTH(value) BETWEEN 87 AND 88)
+-- SHA256HASH: SHA256 CID - base32 = 60 bytes
+--   CHECK (LENGTH(value) BETWEEN 59 AND 60)
+SELECT 1;
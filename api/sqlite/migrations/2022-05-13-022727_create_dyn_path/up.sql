@@ -0,0 +1,19 @@
+-- Your SQL goes here
+CREATE TABLE dyn_paths (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  -- METADATA BEGIN --
+  nonce CHAR(36) NOT NULL CHECK (LENGTH(nonce) = 36),
+  guarantee VARCHAR(44) NOT NULL CHECK (LENGTH(guarantee) BETWEEN 43 AND 44),
+  guarantor VARCHAR(44) NOT NULL CHECK (LENGTH(guarantor) BETWEEN 43 AND 44),
+  guarantee_signature VARCHAR(88) NOT NULL UNIQUE CHECK (LENGTH(guarantee_signature) BETWEEN 87 AND 88),
+  guarantor_signature VARCHAR(88) NOT NULL UNIQUE CHECK (LENGTH(guarantor_signature) BETWEEN 87 AND 88),
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  hash VARCHAR(60) NOT NULL CHECK (LENGTH(hash) BETWEEN 59 AND 60),
+  -- METADATA END --
+  namespace VARCHAR(60) NOT NULL CHECK (LENGTH(namespace) BETWEEN 59 AND 60),
+  kind VARCHAR(60) NOT NULL CHECK (LENGTH(kind) BETWEEN 59 AND 60),
+  word VARCHAR(60) NOT NULL CHECK (LENGTH(word) BETWEEN 59 AND 60),
+  path VARCHAR(60) NOT NULL CHECK (LENGTH(path) BETWEEN 59 AND 60),
+  len BIGINT NOT NULL
+);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE words;
+DROP TABLE words_counts;
+DROP TABLE words_counts_guarantees;
\ No newline at end of file
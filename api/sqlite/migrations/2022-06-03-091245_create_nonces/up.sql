@@ -0,0 +1,9 @@
+-- Your SQL goes here
+CREATE TABLE nonces (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  guarantee VARCHAR(44) NOT NULL CHECK (LENGTH(guarantee) BETWEEN 43 AND 44),
+  nonce CHAR(36) NOT NULL CHECK (LENGTH(nonce) = 36),
+  expiration_date TIMESTAMP,
+  UNIQUE (guarantee, nonce)
+);
+CREATE INDEX nonces_expiration_date ON nonces (expiration_date);
\ No newline at end of file
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE accounts_guarantees_scopes_namespaces;
+DROP TABLE accounts_guarantees_scopes;
\ No newline at end of file
@@ -0,0 +1,26 @@
+-- Your SQL goes here
+CREATE TABLE accounts_guarantees_scopes (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  -- METADATA BEGIN --
+  nonce CHAR(36) NOT NULL CHECK (LENGTH(nonce) = 36),
+  guarantee VARCHAR(44) NOT NULL CHECK (LENGTH(guarantee) BETWEEN 43 AND 44),
+  guarantor VARCHAR(44) NOT NULL CHECK (LENGTH(guarantor) BETWEEN 43 AND 44),
+  guarantee_signature VARCHAR(88) NOT NULL UNIQUE CHECK (LENGTH(guarantee_signature) BETWEEN 87 AND 88),
+  guarantor_signature VARCHAR(88) NOT NULL UNIQUE CHECK (LENGTH(guarantor_signature) BETWEEN 87 AND 88),
+  created_date TIMESTAMP NOT NULL,
+  expiration_date TIMESTAMP,
+  hash VARCHAR(60) NOT NULL CHECK (LENGTH(hash) BETWEEN 59 AND 60),
+  -- METADATA END --
+  account VARCHAR(44) NOT NULL CHECK (LENGTH(account) BETWEEN 43 AND 44),
+  role VARCHAR(10) NOT NULL CHECK (role IN ('read_only', 'read_write', 'admin')),
+  -- all the namespaces if TRUE, or the ones in accounts_guarantees_scopes_namespaces
+  all_namespaces BOOLEAN NOT NULL
+);
+CREATE INDEX accounts_guarantees_scopes_account ON accounts_guarantees_scopes (account, guarantor);
+-- the allowed namespaces of the scopes, in the signed order
+CREATE TABLE accounts_guarantees_scopes_namespaces (
+  id INTEGER PRIMARY KEY AUTOINCREMENT NOT NULL,
+  scope INTEGER NOT NULL REFERENCES accounts_guarantees_scopes (id),
+  namespace VARCHAR(60) NOT NULL CHECK (LENGTH(namespace) BETWEEN 59 AND 60)
+);
+CREATE INDEX accounts_guarantees_scopes_namespaces_scope ON accounts_guarantees_scopes_namespaces (scope);
@@ -0,0 +1,8 @@
+#[macro_use]
+extern crate diesel;
+
+mod models;
+mod schema;
+mod storage;
+
+pub use self::storage::SqliteStorage;
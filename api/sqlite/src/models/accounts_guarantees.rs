@@ -0,0 +1,115 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuarantee {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees)]
+pub struct NewAccountsGuarantee {
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+}
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuaranteesRevocation {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub revoked_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees_revocations)]
+pub struct NewAccountsGuaranteesRevocation {
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub revoked_id: i32,
+}
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuaranteesScope {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub role: String,
+    pub all_namespaces: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees_scopes)]
+pub struct NewAccountsGuaranteesScope {
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub account: String,
+    pub role: String,
+    pub all_namespaces: bool,
+}
+
+#[derive(Debug, Queryable)]
+pub struct AccountsGuaranteesScopeNamespace {
+    pub id: i32,
+    pub scope: i32,
+    pub namespace: String,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::accounts_guarantees_scopes_namespaces)]
+pub struct NewAccountsGuaranteesScopeNamespace {
+    pub scope: i32,
+    pub namespace: String,
+}
@@ -0,0 +1,41 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Debug, Queryable)]
+pub struct DynPath {
+    pub id: i32,
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub namespace: String,
+    pub kind: String,
+    pub word: String,
+    pub path: String,
+    pub len: i64,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::dyn_paths)]
+pub struct NewDynPath {
+    // -- METADATA BEGIN --
+    pub nonce: String,
+    pub guarantee: String,
+    pub guarantor: String,
+    pub guarantee_signature: String,
+    pub guarantor_signature: String,
+    pub created_date: NaiveDateTime,
+    pub expiration_date: Option<NaiveDateTime>,
+    pub hash: String,
+    // -- METADATA END --
+    pub namespace: String,
+    pub kind: String,
+    pub word: String,
+    pub path: String,
+    pub len: i64,
+}
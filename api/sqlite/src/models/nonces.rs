@@ -0,0 +1,9 @@
+use ipis::core::chrono::NaiveDateTime;
+
+#[derive(Insertable)]
+#[diesel(table_name = crate::schema::nonces)]
+pub struct NewNonce {
+    pub guarantee: String,
+    pub nonce: String,
+    pub expiration_date: Option<NaiveDateTime>,
+}
@@ -0,0 +1,1491 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use diesel::{
+    connection::SimpleConnection,
+    dsl::{count_distinct, count_star, max, sql},
+    r2d2::{ConnectionManager, CustomizeConnection, Pool},
+    sql_types::BigInt,
+    upsert::excluded,
+    BoolExpressionMethods, Connection, ExpressionMethods, QueryDsl, RunQueryDsl, SqliteConnection,
+};
+use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
+use ipdis_api_common::{
+    env::infer_optional,
+    nonce::{self, NonceCache},
+    policy::RequestPolicy,
+    quota::{QuotaPolicy, Usage},
+    storage::{blocking, IpdisStorage, PoolConfig, PoolState},
+};
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWordKeyHash, GetWords, GetWordsCounts, GetWordsCountsOutput,
+    GetWordsParent, GuaranteeScope, IpdisError, NamespaceStats, Page, PutWordsError, SignedCursor,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned, Identity},
+        anyhow::{anyhow, bail, Error, Result},
+        chrono::Utc,
+        data::Data,
+        metadata::Metadata,
+        value::{
+            chrono::{DateTime, NaiveDateTime},
+            hash::Hash,
+            text::TextHash,
+            uuid::Uuid,
+        },
+    },
+    env,
+    path::{DynPath, Path},
+    word::{WordHash, WordKeyHash},
+};
+
+/// The migrations of the schema, embedded at build time.
+///
+/// Unlike the `postgres` storage, the nanoseconds are not stored aside:
+/// SQLite keeps the timestamps as text, which diesel writes in full (`%F %T%.f`),
+/// so the signed timestamps are read back as they are.
+const MIGRATIONS: EmbeddedMigrations = embed_migrations!();
+
+pub struct SqliteStorage {
+    connection: Pool<ConnectionManager<SqliteConnection>>,
+    nonces: Arc<NonceCache>,
+}
+
+#[derive(Debug)]
+struct ConnectionOptions;
+
+impl CustomizeConnection<SqliteConnection, ::diesel::r2d2::Error> for ConnectionOptions {
+    fn on_acquire(&self, conn: &mut SqliteConnection) -> Result<(), ::diesel::r2d2::Error> {
+        // wait for the other writers rather than failing immediately
+        conn.batch_execute("PRAGMA busy_timeout = 5000; PRAGMA journal_mode = WAL;")
+            .map_err(::diesel::r2d2::Error::QueryError)
+    }
+}
+
+impl SqliteStorage {
+    /// Connects to the storage, and applies the pending migrations if `migrate` is set.
+    ///
+    /// It blocks until the connections are established and the migrations are done.
+    fn open(migrate: bool) -> Result<Self> {
+        let database_url: String = env::infer("DATABASE_URL")?;
+
+        let pool = PoolConfig::try_infer()?;
+        let mut builder = Pool::builder()
+            .test_on_check_out(true)
+            .connection_customizer(Box::new(ConnectionOptions))
+            .min_idle(pool.min_idle);
+        if let Some(max_size) = pool.max_size {
+            builder = builder.max_size(max_size);
+        }
+        if let Some(timeout) = pool.connection_timeout {
+            builder = builder.connection_timeout(timeout);
+        }
+        if let Some(timeout) = pool.idle_timeout {
+            builder = builder.idle_timeout(Some(timeout));
+        }
+
+        let storage = Self {
+            connection: builder
+                .build(ConnectionManager::<SqliteConnection>::new(&database_url))
+                .or_else(|_| bail!("Error connecting to {database_url}"))?,
+            nonces: NonceCache::try_infer()?.into(),
+        };
+
+        if migrate {
+            storage
+                .connection
+                .get()?
+                .run_pending_migrations(MIGRATIONS)
+                .map_err(|e| anyhow!("failed to migrate the storage: {e}"))?;
+        }
+        Ok(storage)
+    }
+}
+
+#[async_trait]
+impl IpdisStorage for SqliteStorage {
+    async fn try_infer() -> Result<Self> {
+        // the pending migrations are left to the operators if opted out
+        let migrate: bool = infer_optional("ipdis_api_migrate")?.unwrap_or(true);
+        blocking(move || Self::open(migrate)).await
+    }
+
+    async fn try_infer_verify_only() -> Result<Self> {
+        blocking(|| Self::open(false)).await
+    }
+
+    async fn get_pending_migrations(&self) -> Result<u32> {
+        let connection = self.connection.clone();
+        blocking(move || {
+            let migrations = connection
+                .get()?
+                .pending_migrations(MIGRATIONS)
+                .map_err(|e| anyhow!(e))?;
+            Ok(migrations.len() as u32)
+        })
+        .await
+    }
+
+    fn pool_state(&self) -> Option<PoolState> {
+        let state = self.connection.state();
+        Some(PoolState {
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+        })
+    }
+
+    async fn contains_guarantee(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        blocking(move || {
+            let mut conn = connection.get()?;
+
+            let revoked_id: Option<i32> = crate::schema::accounts_guarantees_revocations::table
+                .filter(
+                    crate::schema::accounts_guarantees_revocations::account
+                        .eq(guarantee.to_string()),
+                )
+                .filter(
+                    crate::schema::accounts_guarantees_revocations::guarantor
+                        .eq(guarantor.to_string()),
+                )
+                .select(max(
+                    crate::schema::accounts_guarantees_revocations::revoked_id,
+                ))
+                .get_result(&mut conn)?;
+
+            let mut query = crate::schema::accounts_guarantees::table
+                .limit(1)
+                .filter(crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()))
+                .filter(crate::schema::accounts_guarantees::guarantor.eq(guarantor.to_string()))
+                .filter(
+                    crate::schema::accounts_guarantees::expiration_date
+                        .ge(Utc::now().naive_utc())
+                        .or(crate::schema::accounts_guarantees::expiration_date.is_null()),
+                )
+                .into_boxed();
+
+            // ignore the guarantees put before the latest revocation
+            if let Some(revoked_id) = revoked_id {
+                query = query.filter(crate::schema::accounts_guarantees::id.gt(revoked_id));
+            }
+
+            query
+                .execute(&mut conn)
+                .map(|count| count > 0)
+                .map_err(Into::into)
+        })
+        .await
+    }
+
+    async fn put_guarantee(&self, guarantee: &Data<GuarantorSigned, AccountRef>) -> Result<()> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        blocking(move || {
+            let record = crate::models::accounts_guarantees::NewAccountsGuarantee {
+                nonce: guarantee.metadata.nonce.0 .0.to_string(),
+                guarantee: guarantee.metadata.guarantee.account.to_string(),
+                guarantor: guarantee.metadata.guarantor.account.to_string(),
+                guarantee_signature: guarantee.metadata.guarantee.signature.to_string(),
+                guarantor_signature: guarantee.metadata.guarantor.signature.to_string(),
+                created_date: guarantee.metadata.created_date.naive_utc(),
+                expiration_date: guarantee.metadata.expiration_date.map(|e| e.naive_utc()),
+                hash: guarantee.metadata.hash.to_string(),
+            };
+
+            ::diesel::insert_into(crate::schema::accounts_guarantees::table)
+                .values(&record)
+                .execute(&mut connection.get()?)
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+        .await
+    }
+
+    async fn get_guarantee_many(
+        &self,
+        guarantor: &AccountRef,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        let connection = self.connection.clone();
+        let guarantor = *guarantor;
+        let query = query.clone();
+        blocking(move || {
+            let mut sql = crate::schema::accounts_guarantees::table
+                // exclude the guarantees put before any revocation
+                .left_join(
+                    crate::schema::accounts_guarantees_revocations::table.on(
+                        crate::schema::accounts_guarantees_revocations::account
+                            .eq(crate::schema::accounts_guarantees::guarantee)
+                            .and(
+                                crate::schema::accounts_guarantees_revocations::guarantor
+                                    .eq(crate::schema::accounts_guarantees::guarantor),
+                            )
+                            .and(
+                                crate::schema::accounts_guarantees_revocations::revoked_id
+                                    .ge(crate::schema::accounts_guarantees::id),
+                            ),
+                    ),
+                )
+                .filter(crate::schema::accounts_guarantees_revocations::id.is_null())
+                .select(crate::schema::accounts_guarantees::all_columns)
+                .order(crate::schema::accounts_guarantees::id.desc())
+                .offset(query.start_index.into())
+                .limit((query.end_index - query.start_index).into())
+                .filter(crate::schema::accounts_guarantees::guarantor.eq(guarantor.to_string()))
+                .filter(
+                    crate::schema::accounts_guarantees::expiration_date
+                        .ge(Utc::now().naive_utc())
+                        .or(crate::schema::accounts_guarantees::expiration_date.is_null()),
+                )
+                .into_boxed();
+
+            if let Some(guarantee) = &query.guarantee {
+                sql = sql.filter(
+                    crate::schema::accounts_guarantees::guarantee.eq(guarantee.to_string()),
+                );
+            }
+
+            let records: Vec<crate::models::accounts_guarantees::AccountsGuarantee> =
+                sql.get_results(&mut connection.get()?)?;
+
+            records
+                .into_iter()
+                .map(|record| {
+                    Ok(Data {
+                        metadata: GuarantorSigned {
+                            guarantor: Identity {
+                                account: AccountRef {
+                                    public_key: record.guarantor.parse()?,
+                                },
+                                signature: record.guarantor_signature.parse()?,
+                            },
+                            data: GuaranteeSigned {
+                                guarantee: Identity {
+                                    account: AccountRef {
+                                        public_key: record.guarantee.parse()?,
+                                    },
+                                    signature: record.guarantee_signature.parse()?,
+                                },
+                                data: Metadata {
+                                    nonce: Uuid(record.nonce.parse()?).into(),
+                                    created_date: NaiveDateTime(record.created_date).to_utc(),
+                                    expiration_date: record
+                                        .expiration_date
+                                        .map(|e| NaiveDateTime(e).to_utc()),
+                                    guarantor: record.guarantor.parse()?,
+                                    hash: record.hash.parse()?,
+                                },
+                            },
+                        },
+                        data: AccountRef {
+                            public_key: record.guarantee.parse()?,
+                        },
+                    })
+                })
+                .collect()
+        })
+        .await
+    }
+
+    async fn delete_guarantee_all(&self, guarantee: &AccountRef) -> Result<()> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        blocking(move || {
+            let guarantee = guarantee.to_string();
+
+            // the scopes are given to the guarantees, so they are deleted together
+            connection
+                .get()?
+                .immediate_transaction::<_, Error, _>(|conn| {
+                    ::diesel::delete(crate::schema::accounts_guarantees::table)
+                        .filter(crate::schema::accounts_guarantees::guarantee.eq(&guarantee))
+                        .execute(conn)?;
+
+                    let scopes = crate::schema::accounts_guarantees_scopes::table
+                        .filter(crate::schema::accounts_guarantees_scopes::account.eq(&guarantee))
+                        .select(crate::schema::accounts_guarantees_scopes::id);
+                    ::diesel::delete(crate::schema::accounts_guarantees_scopes_namespaces::table)
+                        .filter(
+                            crate::schema::accounts_guarantees_scopes_namespaces::scope
+                                .eq_any(scopes),
+                        )
+                        .execute(conn)?;
+                    ::diesel::delete(crate::schema::accounts_guarantees_scopes::table)
+                        .filter(crate::schema::accounts_guarantees_scopes::account.eq(&guarantee))
+                        .execute(conn)?;
+                    Ok(())
+                })
+        })
+        .await
+    }
+
+    async fn get_guarantee_revocation_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, AccountRef>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        blocking(move || {
+            let mut records: Vec<crate::models::accounts_guarantees::AccountsGuaranteesRevocation> =
+                crate::schema::accounts_guarantees_revocations::table
+                    // the latest one is the last one put, not the last one signed
+                    .order(crate::schema::accounts_guarantees_revocations::id.desc())
+                    .limit(1)
+                    .filter(
+                        crate::schema::accounts_guarantees_revocations::account
+                            .eq(guarantee.to_string()),
+                    )
+                    .filter(
+                        crate::schema::accounts_guarantees_revocations::guarantor
+                            .eq(guarantor.to_string()),
+                    )
+                    .get_results(&mut connection.get()?)?;
+
+            match records.pop() {
+                Some(record) => Ok(Some(Data {
+                    metadata: GuarantorSigned {
+                        guarantor: Identity {
+                            account: AccountRef {
+                                public_key: record.guarantor.parse()?,
+                            },
+                            signature: record.guarantor_signature.parse()?,
+                        },
+                        data: GuaranteeSigned {
+                            guarantee: Identity {
+                                account: AccountRef {
+                                    public_key: record.guarantee.parse()?,
+                                },
+                                signature: record.guarantee_signature.parse()?,
+                            },
+                            data: Metadata {
+                                nonce: Uuid(record.nonce.parse()?).into(),
+                                created_date: NaiveDateTime(record.created_date).to_utc(),
+                                expiration_date: record
+                                    .expiration_date
+                                    .map(|e| NaiveDateTime(e).to_utc()),
+                                guarantor: record.guarantor.parse()?,
+                                hash: record.hash.parse()?,
+                            },
+                        },
+                    },
+                    data: AccountRef {
+                        public_key: record.account.parse()?,
+                    },
+                })),
+                None => Ok(None),
+            }
+        })
+        .await
+    }
+
+    async fn put_guarantee_revocation(
+        &self,
+        revocation: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()> {
+        let connection = self.connection.clone();
+        let revocation = *revocation;
+        blocking(move || {
+            let metadata = &revocation.metadata;
+            let account = revocation.data.to_string();
+            let guarantor = metadata.guarantor.account.to_string();
+
+            let mut conn = connection.get()?;
+            conn.immediate_transaction::<_, Error, _>(|conn| {
+                // the guarantees put by now are revoked, but not the ones put later
+                let revoked_id: Option<i32> = crate::schema::accounts_guarantees::table
+                    .filter(crate::schema::accounts_guarantees::guarantee.eq(&account))
+                    .filter(crate::schema::accounts_guarantees::guarantor.eq(&guarantor))
+                    .select(max(crate::schema::accounts_guarantees::id))
+                    .get_result(conn)?;
+
+                let record = crate::models::accounts_guarantees::NewAccountsGuaranteesRevocation {
+                    nonce: metadata.nonce.0 .0.to_string(),
+                    guarantee: metadata.guarantee.account.to_string(),
+                    guarantor: guarantor.clone(),
+                    guarantee_signature: metadata.guarantee.signature.to_string(),
+                    guarantor_signature: metadata.guarantor.signature.to_string(),
+                    created_date: metadata.created_date.naive_utc(),
+                    expiration_date: metadata.expiration_date.map(|e| e.naive_utc()),
+                    hash: metadata.hash.to_string(),
+                    account: account.clone(),
+                    revoked_id: revoked_id.unwrap_or_default(),
+                };
+
+                ::diesel::insert_into(crate::schema::accounts_guarantees_revocations::table)
+                    .values(&record)
+                    .execute(conn)?;
+                Ok(())
+            })
+        })
+        .await
+    }
+
+    async fn get_guarantee_scope_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, GuaranteeScope>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        blocking(move || {
+            let mut conn = connection.get()?;
+
+            let mut records: Vec<crate::models::accounts_guarantees::AccountsGuaranteesScope> =
+                crate::schema::accounts_guarantees_scopes::table
+                    // the latest one is the last one put, not the last one signed
+                    .order(crate::schema::accounts_guarantees_scopes::id.desc())
+                    .limit(1)
+                    .filter(
+                        crate::schema::accounts_guarantees_scopes::account
+                            .eq(guarantee.to_string()),
+                    )
+                    .filter(
+                        crate::schema::accounts_guarantees_scopes::guarantor
+                            .eq(guarantor.to_string()),
+                    )
+                    .get_results(&mut conn)?;
+
+            match records.pop() {
+                Some(record) => Ok(Some(Data {
+                    metadata: GuarantorSigned {
+                        guarantor: Identity {
+                            account: AccountRef {
+                                public_key: record.guarantor.parse()?,
+                            },
+                            signature: record.guarantor_signature.parse()?,
+                        },
+                        data: GuaranteeSigned {
+                            guarantee: Identity {
+                                account: AccountRef {
+                                    public_key: record.guarantee.parse()?,
+                                },
+                                signature: record.guarantee_signature.parse()?,
+                            },
+                            data: Metadata {
+                                nonce: Uuid(record.nonce.parse()?).into(),
+                                created_date: NaiveDateTime(record.created_date).to_utc(),
+                                expiration_date: record
+                                    .expiration_date
+                                    .map(|e| NaiveDateTime(e).to_utc()),
+                                guarantor: record.guarantor.parse()?,
+                                hash: record.hash.parse()?,
+                            },
+                        },
+                    },
+                    data: GuaranteeScope {
+                        account: AccountRef {
+                            public_key: record.account.parse()?,
+                        },
+                        role: record.role.parse()?,
+                        namespaces: if record.all_namespaces {
+                            None
+                        } else {
+                            use crate::schema::accounts_guarantees_scopes_namespaces as table;
+
+                            Some(
+                                table::table
+                                    .filter(table::scope.eq(record.id))
+                                    .order(table::id.asc())
+                                    .select(table::namespace)
+                                    .load::<String>(&mut conn)?
+                                    .iter()
+                                    .map(|namespace| namespace.parse())
+                                    .collect::<Result<_, _>>()?,
+                            )
+                        },
+                    },
+                })),
+                None => Ok(None),
+            }
+        })
+        .await
+    }
+
+    async fn put_guarantee_scope(
+        &self,
+        scope: &Data<GuarantorSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        let connection = self.connection.clone();
+        let scope = scope.clone();
+        blocking(move || {
+            let record = crate::models::accounts_guarantees::NewAccountsGuaranteesScope {
+                nonce: scope.metadata.nonce.0 .0.to_string(),
+                guarantee: scope.metadata.guarantee.account.to_string(),
+                guarantor: scope.metadata.guarantor.account.to_string(),
+                guarantee_signature: scope.metadata.guarantee.signature.to_string(),
+                guarantor_signature: scope.metadata.guarantor.signature.to_string(),
+                created_date: scope.metadata.created_date.naive_utc(),
+                expiration_date: scope.metadata.expiration_date.map(|e| e.naive_utc()),
+                hash: scope.metadata.hash.to_string(),
+                account: scope.data.account.to_string(),
+                role: scope.data.role.to_string(),
+                all_namespaces: scope.data.namespaces.is_none(),
+            };
+
+            connection
+                .get()?
+                .immediate_transaction::<_, Error, _>(|conn| {
+                    ::diesel::insert_into(crate::schema::accounts_guarantees_scopes::table)
+                        .values(&record)
+                        .execute(conn)?;
+
+                    if let Some(namespaces) = &scope.data.namespaces {
+                        use crate::models::accounts_guarantees::NewAccountsGuaranteesScopeNamespace;
+
+                        let id = crate::schema::accounts_guarantees_scopes::table
+                            .filter(
+                                crate::schema::accounts_guarantees_scopes::guarantee_signature
+                                    .eq(&record.guarantee_signature),
+                            )
+                            .select(crate::schema::accounts_guarantees_scopes::id)
+                            .get_result(conn)?;
+
+                        let records: Vec<_> = namespaces
+                            .iter()
+                            .map(|namespace| NewAccountsGuaranteesScopeNamespace {
+                                scope: id,
+                                namespace: namespace.to_string(),
+                            })
+                            .collect();
+                        ::diesel::insert_into(
+                            crate::schema::accounts_guarantees_scopes_namespaces::table,
+                        )
+                        .values(&records)
+                        .execute(conn)?;
+                    }
+                    Ok(())
+                })
+        })
+        .await
+    }
+
+    async fn put_nonce(
+        &self,
+        request: &GuaranteeSigned,
+        retention_date: ::ipis::core::chrono::NaiveDateTime,
+    ) -> Result<bool> {
+        // the replays seen lately are rejected without querying the table
+        if self.nonces.contains(request) {
+            return Ok(false);
+        }
+
+        let connection = self.connection.clone();
+        let nonces = self.nonces.clone();
+        let request = *request;
+        blocking(move || {
+            let record = nonce_record(&request, retention_date);
+            let inserted = insert_nonce(&mut connection.get()?, &record)?;
+
+            nonces.insert(&request, retention_date);
+            Ok(inserted)
+        })
+        .await
+    }
+
+    async fn sweep_nonces(&self) -> Result<()> {
+        self.nonces.sweep();
+
+        let connection = self.connection.clone();
+        blocking(move || {
+            // forget the expired nonces, including the ones kept forever by the older versions
+            ::diesel::delete(crate::schema::nonces::table)
+                .filter(
+                    crate::schema::nonces::expiration_date
+                        .lt(Utc::now().naive_utc())
+                        .or(crate::schema::nonces::expiration_date.is_null()),
+                )
+                .execute(&mut connection.get()?)
+                .map(|_| ())
+                .map_err(Into::into)
+        })
+        .await
+    }
+
+    async fn get_dyn_path(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        let path = *path;
+        blocking(move || {
+            let mut records: Vec<crate::models::dyn_paths::DynPath> =
+                crate::schema::dyn_paths::table
+                    .order(crate::schema::dyn_paths::created_date.desc())
+                    .limit(1)
+                    .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+                    .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+                    .filter(
+                        crate::schema::dyn_paths::expiration_date
+                            .ge(Utc::now().naive_utc())
+                            .or(crate::schema::dyn_paths::expiration_date.is_null()),
+                    )
+                    .filter(crate::schema::dyn_paths::namespace.eq(path.namespace.to_string()))
+                    .filter(crate::schema::dyn_paths::kind.eq(path.kind.to_string()))
+                    .filter(crate::schema::dyn_paths::word.eq(path.word.to_string()))
+                    .get_results(&mut connection.get()?)?;
+
+            records.pop().map(dyn_path_from_record).transpose()
+        })
+        .await
+    }
+
+    async fn get_dyn_path_at(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        let path = *path;
+        let timestamp = *timestamp;
+        blocking(move || {
+            let timestamp = timestamp.naive_utc();
+
+            let mut records: Vec<crate::models::dyn_paths::DynPath> =
+                crate::schema::dyn_paths::table
+                    .order(crate::schema::dyn_paths::created_date.desc())
+                    .limit(1)
+                    .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+                    .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+                    .filter(crate::schema::dyn_paths::created_date.le(timestamp))
+                    .filter(
+                        crate::schema::dyn_paths::expiration_date
+                            .ge(timestamp)
+                            .or(crate::schema::dyn_paths::expiration_date.is_null()),
+                    )
+                    .filter(crate::schema::dyn_paths::namespace.eq(path.namespace.to_string()))
+                    .filter(crate::schema::dyn_paths::kind.eq(path.kind.to_string()))
+                    .filter(crate::schema::dyn_paths::word.eq(path.word.to_string()))
+                    .get_results(&mut connection.get()?)?;
+
+            records.pop().map(dyn_path_from_record).transpose()
+        })
+        .await
+    }
+
+    async fn get_dyn_path_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        let query = query.clone();
+        blocking(move || {
+            let mut sql = crate::schema::dyn_paths::table
+                .order(crate::schema::dyn_paths::id.desc())
+                .offset(query.start_index.into())
+                .limit((query.end_index - query.start_index).into())
+                .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+                .filter(crate::schema::dyn_paths::guarantor.eq(guarantor.to_string()))
+                .filter(crate::schema::dyn_paths::namespace.eq(query.path.namespace.to_string()))
+                .filter(crate::schema::dyn_paths::kind.eq(query.path.kind.to_string()))
+                .filter(crate::schema::dyn_paths::word.eq(query.path.word.to_string()))
+                .into_boxed();
+
+            if let Some(since) = &query.since {
+                sql = sql.filter(crate::schema::dyn_paths::created_date.ge(since.naive_utc()));
+            }
+            if let Some(until) = &query.until {
+                sql = sql.filter(crate::schema::dyn_paths::created_date.le(until.naive_utc()));
+            }
+
+            // continue after the last seen record
+            if let Some(cursor) = &query.cursor {
+                sql = sql.filter(crate::schema::dyn_paths::id.lt(cursor_id(cursor)?));
+            }
+
+            let records: Vec<crate::models::dyn_paths::DynPath> =
+                sql.get_results(&mut connection.get()?)?;
+
+            let records = records
+                .into_iter()
+                .map(|record| Ok((u64::try_from(record.id)?, dyn_path_from_record(record)?)))
+                .collect::<Result<_>>()?;
+            Ok(Page::with_ids(records, query.end_index - query.start_index))
+        })
+        .await
+    }
+
+    async fn put_dyn_path(
+        &self,
+        path: &Data<GuarantorSigned, DynPath<Path>>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()> {
+        if self.nonces.contains(&path.metadata) {
+            bail!(nonce::replayed())
+        }
+
+        let connection = self.connection.clone();
+        let nonces = self.nonces.clone();
+        let path = *path;
+        let quota = *quota;
+        let retention_date = policy.retention_date(&path.metadata);
+        blocking(move || {
+            let record = crate::models::dyn_paths::NewDynPath {
+                nonce: path.metadata.nonce.0 .0.to_string(),
+                guarantee: path.metadata.guarantee.account.to_string(),
+                guarantor: path.metadata.guarantor.account.to_string(),
+                guarantee_signature: path.metadata.guarantee.signature.to_string(),
+                guarantor_signature: path.metadata.guarantor.signature.to_string(),
+                created_date: path.metadata.created_date.naive_utc(),
+                expiration_date: path.metadata.expiration_date.map(|e| e.naive_utc()),
+                hash: path.metadata.hash.to_string(),
+                namespace: path.data.namespace.to_string(),
+                kind: path.data.kind.to_string(),
+                word: path.data.word.to_string(),
+                path: path.data.path.value.to_string(),
+                len: path.data.path.len.try_into()?,
+            };
+
+            connection
+                .get()?
+                .immediate_transaction::<_, Error, _>(|conn| {
+                    // the immediate transaction blocks the other writers until the count is checked
+                    if quota.max_dyn_paths.is_some() {
+                        let dyn_paths: i64 = crate::schema::dyn_paths::table
+                            .filter(crate::schema::dyn_paths::guarantee.eq(&record.guarantee))
+                            .count()
+                            .get_result(conn)?;
+                        let usage = Usage {
+                            words: 0,
+                            dyn_paths: dyn_paths.try_into()?,
+                        };
+                        quota.ensure_available(&usage, 0, 1)?;
+                    }
+
+                    // record the nonce along with the path
+                    if !insert_nonce(conn, &nonce_record(&path.metadata, retention_date))? {
+                        bail!(nonce::replayed())
+                    }
+
+                    ::diesel::insert_into(crate::schema::dyn_paths::table)
+                        .values(&record)
+                        .execute(conn)?;
+                    Ok(())
+                })?;
+
+            nonces.insert(&path.metadata, retention_date);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn delete_dyn_path_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        let connection = self.connection.clone();
+        let guarantee = guarantee.copied();
+        let namespace = *namespace;
+        blocking(move || {
+            let sql = ::diesel::delete(crate::schema::dyn_paths::table)
+                .filter(crate::schema::dyn_paths::namespace.eq(namespace.to_string()));
+
+            match guarantee {
+                Some(guarantee) => sql
+                    .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+                    .execute(&mut connection.get()?)?,
+                None => sql.execute(&mut connection.get()?)?,
+            };
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_word_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let guarantor = *guarantor;
+        let query = query.clone();
+        blocking(move || {
+            let mut sql = crate::schema::words::table
+                .order(crate::schema::words::id.desc())
+                .offset(query.start_index.into())
+                .limit((query.end_index - query.start_index).into())
+                .filter(crate::schema::words::guarantee.eq(guarantee.to_string()))
+                .filter(crate::schema::words::guarantor.eq(guarantor.to_string()))
+                .filter(
+                    crate::schema::words::expiration_date
+                        .ge(Utc::now().naive_utc())
+                        .or(crate::schema::words::expiration_date.is_null()),
+                )
+                .filter(crate::schema::words::namespace.eq(query.word.namespace.to_string()))
+                .filter(crate::schema::words::lang.eq(query.word.text.lang.to_string()))
+                .into_boxed();
+
+            // continue after the last seen record
+            if let Some(cursor) = &query.cursor {
+                sql = sql.filter(crate::schema::words::id.lt(cursor_id(cursor)?));
+            }
+
+            let records: Vec<crate::models::words::Word> = match query.parent {
+                GetWordsParent::None => sql
+                    .filter(crate::schema::words::word.eq(query.word.text.msg.to_string()))
+                    .get_results(&mut connection.get()?)?,
+                GetWordsParent::Duplicated => sql
+                    .filter(crate::schema::words::parent.eq(query.word.text.msg.to_string()))
+                    .get_results(&mut connection.get()?)?,
+            };
+
+            let records = records
+                .into_iter()
+                .map(|record| {
+                    let id = u64::try_from(record.id)?;
+                    let record = Data {
+                        metadata: GuarantorSigned {
+                            guarantor: Identity {
+                                account: AccountRef {
+                                    public_key: record.guarantor.parse()?,
+                                },
+                                signature: record.guarantor_signature.parse()?,
+                            },
+                            data: GuaranteeSigned {
+                                guarantee: Identity {
+                                    account: AccountRef {
+                                        public_key: record.guarantee.parse()?,
+                                    },
+                                    signature: record.guarantee_signature.parse()?,
+                                },
+                                data: Metadata {
+                                    nonce: Uuid(record.nonce.parse()?).into(),
+                                    created_date: NaiveDateTime(record.created_date).to_utc(),
+                                    expiration_date: record
+                                        .expiration_date
+                                        .map(|e| NaiveDateTime(e).to_utc()),
+                                    guarantor: record.guarantor.parse()?,
+                                    hash: record.hash.parse()?,
+                                },
+                            },
+                        },
+                        data: WordHash {
+                            key: WordKeyHash {
+                                namespace: record.namespace.parse()?,
+                                text: TextHash {
+                                    lang: record.lang.parse()?,
+                                    msg: record.word.parse()?,
+                                },
+                            },
+                            kind: record.kind.parse()?,
+                            relpath: record.relpath,
+                            path: Path {
+                                value: record.path.parse()?,
+                                len: record.len.try_into()?,
+                            },
+                        },
+                    };
+                    Ok((id, record))
+                })
+                .collect::<Result<_>>()?;
+            Ok(Page::with_ids(records, query.end_index - query.start_index))
+        })
+        .await
+    }
+
+    async fn get_word_count_many(
+        &self,
+        guarantee: &AccountRef,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        let query = query.clone();
+        blocking(move || {
+            let records = if query.owned {
+                let mut sql = crate::schema::words_counts_guarantees::table
+                    .order(crate::schema::words_counts_guarantees::id.desc())
+                    .offset(query.start_index.into())
+                    .limit((query.end_index - query.start_index).into())
+                    .filter(
+                        crate::schema::words_counts_guarantees::guarantee.eq(guarantee.to_string()),
+                    )
+                    .filter(
+                        crate::schema::words_counts_guarantees::namespace
+                            .eq(query.word.namespace.to_string()),
+                    )
+                    .filter(
+                        crate::schema::words_counts_guarantees::lang.eq(query
+                            .word
+                            .text
+                            .lang
+                            .to_string()),
+                    )
+                    .into_boxed();
+
+                // continue after the last seen record
+                if let Some(cursor) = &query.cursor {
+                    sql = sql
+                        .filter(crate::schema::words_counts_guarantees::id.lt(cursor_id(cursor)?));
+                }
+
+                let records: Vec<crate::models::words::WordCountGuarantee> = if query.parent {
+                    sql.filter(
+                        crate::schema::words_counts_guarantees::parent.eq(query
+                            .word
+                            .text
+                            .msg
+                            .to_string()),
+                    )
+                    .get_results(&mut connection.get()?)?
+                } else {
+                    sql.filter(
+                        crate::schema::words_counts_guarantees::word.eq(query
+                            .word
+                            .text
+                            .msg
+                            .to_string()),
+                    )
+                    .get_results(&mut connection.get()?)?
+                };
+
+                records
+                    .into_iter()
+                    .map(|record| {
+                        Ok((
+                            u64::try_from(record.id)?,
+                            GetWordsCountsOutput {
+                                word: GetWordKeyHash {
+                                    key: WordKeyHash {
+                                        namespace: record.namespace.parse()?,
+                                        text: TextHash {
+                                            lang: record.lang.parse()?,
+                                            msg: record.word.parse()?,
+                                        },
+                                    },
+                                    kind: record.kind.parse()?,
+                                },
+                                count: record.count.try_into()?,
+                            },
+                        ))
+                    })
+                    .collect::<Result<_>>()?
+            } else {
+                let mut sql = crate::schema::words_counts::table
+                    .order(crate::schema::words_counts::id.desc())
+                    .offset(query.start_index.into())
+                    .limit((query.end_index - query.start_index).into())
+                    .filter(
+                        crate::schema::words_counts::namespace.eq(query.word.namespace.to_string()),
+                    )
+                    .filter(crate::schema::words_counts::lang.eq(query.word.text.lang.to_string()))
+                    .into_boxed();
+
+                // continue after the last seen record
+                if let Some(cursor) = &query.cursor {
+                    sql = sql.filter(crate::schema::words_counts::id.lt(cursor_id(cursor)?));
+                }
+
+                let records: Vec<crate::models::words::WordCount> = if query.parent {
+                    sql.filter(
+                        crate::schema::words_counts::parent.eq(query.word.text.msg.to_string()),
+                    )
+                    .get_results(&mut connection.get()?)?
+                } else {
+                    sql.filter(
+                        crate::schema::words_counts::word.eq(query.word.text.msg.to_string()),
+                    )
+                    .get_results(&mut connection.get()?)?
+                };
+
+                records
+                    .into_iter()
+                    .map(|record| {
+                        Ok((
+                            u64::try_from(record.id)?,
+                            GetWordsCountsOutput {
+                                word: GetWordKeyHash {
+                                    key: WordKeyHash {
+                                        namespace: record.namespace.parse()?,
+                                        text: TextHash {
+                                            lang: record.lang.parse()?,
+                                            msg: record.word.parse()?,
+                                        },
+                                    },
+                                    kind: record.kind.parse()?,
+                                },
+                                count: record.count.try_into()?,
+                            },
+                        ))
+                    })
+                    .collect::<Result<_>>()?
+            };
+            Ok(Page::with_ids(records, query.end_index - query.start_index))
+        })
+        .await
+    }
+
+    async fn get_namespace_stats(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        let connection = self.connection.clone();
+        let namespace = *namespace;
+        blocking(move || {
+            let (total_words, distinct_words, distinct_guarantees): (i64, i64, i64) =
+                crate::schema::words::table
+                    .filter(crate::schema::words::namespace.eq(namespace.to_string()))
+                    .select((
+                        count_star(),
+                        // the words are distinguished by their languages and texts
+                        sql::<BigInt>("COUNT(DISTINCT lang || ':' || word)"),
+                        count_distinct(crate::schema::words::guarantee),
+                    ))
+                    .get_result(&mut connection.get()?)?;
+
+            Ok(NamespaceStats {
+                total_words: total_words.try_into()?,
+                distinct_words: distinct_words.try_into()?,
+                distinct_guarantees: distinct_guarantees.try_into()?,
+            })
+        })
+        .await
+    }
+
+    async fn get_usage(&self, guarantee: &AccountRef) -> Result<Usage> {
+        let connection = self.connection.clone();
+        let guarantee = *guarantee;
+        blocking(move || {
+            let mut conn = connection.get()?;
+
+            let words: i64 = crate::schema::words::table
+                .filter(crate::schema::words::guarantee.eq(guarantee.to_string()))
+                .count()
+                .get_result(&mut conn)?;
+            let dyn_paths: i64 = crate::schema::dyn_paths::table
+                .filter(crate::schema::dyn_paths::guarantee.eq(guarantee.to_string()))
+                .count()
+                .get_result(&mut conn)?;
+
+            Ok(Usage {
+                words: words.try_into()?,
+                dyn_paths: dyn_paths.try_into()?,
+            })
+        })
+        .await
+    }
+
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuarantorSigned, WordHash>)],
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<Vec<PutWordsError>> {
+        let connection = self.connection.clone();
+        let nonces = self.nonces.clone();
+        let words = words.to_vec();
+        let quota = *quota;
+        let policy = *policy;
+        blocking(move || {
+            let mut errors = vec![];
+            let mut indices = vec![];
+            let mut records = vec![];
+            for (index, (parent, word)) in words.iter().enumerate() {
+                // the replays seen lately are rejected without querying the table
+                if nonces.contains(&word.metadata) {
+                    errors.push(PutWordsError {
+                        index: index.try_into()?,
+                        error: nonce::replayed(),
+                    });
+                    continue;
+                }
+
+                let len = match word.data.path.len.try_into() {
+                    Ok(len) => len,
+                    Err(e) => {
+                        errors.push(PutWordsError {
+                            index: index.try_into()?,
+                            error: IpdisError::MalformedQuery(format!("malformed path: {e}")),
+                        });
+                        continue;
+                    }
+                };
+
+                indices.push(index);
+                records.push((
+                    nonce_record(&word.metadata, policy.retention_date(&word.metadata)),
+                    crate::models::words::NewWord {
+                        nonce: word.metadata.nonce.0 .0.to_string(),
+                        guarantee: word.metadata.guarantee.account.to_string(),
+                        guarantor: word.metadata.guarantor.account.to_string(),
+                        guarantee_signature: word.metadata.guarantee.signature.to_string(),
+                        guarantor_signature: word.metadata.guarantor.signature.to_string(),
+                        created_date: word.metadata.created_date.naive_utc(),
+                        expiration_date: word.metadata.expiration_date.map(|e| e.naive_utc()),
+                        hash: word.metadata.hash.to_string(),
+                        namespace: word.data.key.namespace.to_string(),
+                        parent: parent.to_string(),
+                        lang: word.data.key.text.lang.to_string(),
+                        word: word.data.key.text.msg.to_string(),
+                        kind: word.data.kind.to_string(),
+                        relpath: word.data.relpath,
+                        path: word.data.path.value.to_string(),
+                        len,
+                    },
+                ));
+            }
+
+            let (recorded, rejected, replayed, exhausted) = connection
+                .get()?
+                .immediate_transaction::<_, Error, _>(|conn| {
+                    // the immediate transaction blocks the other writers until the count is checked
+                    let mut available: BTreeMap<&str, u64> = BTreeMap::default();
+                    if let Some(max_words) = quota.max_words {
+                        for (_, record) in &records {
+                            if !available.contains_key(record.guarantee.as_str()) {
+                                let words: i64 = crate::schema::words::table
+                                    .filter(crate::schema::words::guarantee.eq(&record.guarantee))
+                                    .count()
+                                    .get_result(conn)?;
+                                available.insert(
+                                    &record.guarantee,
+                                    max_words.saturating_sub(words.try_into()?),
+                                );
+                            }
+                        }
+                    }
+
+                    // insert the word records along with their nonces,
+                    // skipping the conflicting ones, the replayed ones and the ones over the quota
+                    let mut recorded = vec![];
+                    let mut rejected = vec![];
+                    let mut replayed = vec![];
+                    let mut exhausted = vec![];
+                    let mut accepted = vec![];
+                    for (index, (nonce, record)) in indices.iter().zip(&records) {
+                        let available = available.get_mut(record.guarantee.as_str());
+                        if available.as_deref() == Some(&0) {
+                            exhausted.push(*index);
+                            continue;
+                        }
+                        if !insert_nonce(conn, nonce)? {
+                            replayed.push(*index);
+                            continue;
+                        }
+                        recorded.push(*index);
+
+                        let inserted = ::diesel::insert_into(crate::schema::words::table)
+                            .values(record)
+                            .on_conflict_do_nothing()
+                            .execute(conn)?;
+                        if inserted > 0 {
+                            if let Some(available) = available {
+                                *available -= 1;
+                            }
+                            accepted.push(record);
+                        } else {
+                            rejected.push(*index);
+                        }
+                    }
+
+                    // aggregate the counts of the inserted words, to update each count only once
+                    // (and in the same order, so that the concurrent writers do not deadlock)
+                    let mut counts_guarantees: BTreeMap<_, i64> = BTreeMap::default();
+                    for record in &accepted {
+                        *counts_guarantees
+                            .entry((
+                                &record.guarantee,
+                                &record.namespace,
+                                &record.kind,
+                                &record.parent,
+                                &record.lang,
+                                &record.word,
+                            ))
+                            .or_default() += 1;
+                    }
+                    let mut counts: BTreeMap<_, i64> = BTreeMap::default();
+                    for ((_, namespace, kind, parent, lang, word), count) in &counts_guarantees {
+                        *counts
+                            .entry((*namespace, *kind, *parent, *lang, *word))
+                            .or_default() += count;
+                    }
+
+                    // append the counts atomically
+                    for ((namespace, kind, parent, lang, word), count) in &counts {
+                        let word_record = crate::models::words::NewWordCount {
+                            namespace: namespace.to_string(),
+                            kind: kind.to_string(),
+                            parent: parent.to_string(),
+                            lang: lang.to_string(),
+                            word: word.to_string(),
+                            count: *count,
+                        };
+
+                        ::diesel::insert_into(crate::schema::words_counts::table)
+                            .values(&word_record)
+                            .on_conflict((
+                                crate::schema::words_counts::namespace,
+                                crate::schema::words_counts::kind,
+                                crate::schema::words_counts::parent,
+                                crate::schema::words_counts::lang,
+                                crate::schema::words_counts::word,
+                            ))
+                            .do_update()
+                            .set(
+                                crate::schema::words_counts::count
+                                    .eq(crate::schema::words_counts::count
+                                        + excluded(crate::schema::words_counts::count)),
+                            )
+                            .execute(conn)?;
+                    }
+
+                    // append the counts of guarantee atomically
+                    for ((guarantee, namespace, kind, parent, lang, word), count) in
+                        &counts_guarantees
+                    {
+                        let word_record = crate::models::words::NewWordCountGuarantee {
+                            guarantee: guarantee.to_string(),
+                            namespace: namespace.to_string(),
+                            kind: kind.to_string(),
+                            parent: parent.to_string(),
+                            lang: lang.to_string(),
+                            word: word.to_string(),
+                            count: *count,
+                        };
+
+                        ::diesel::insert_into(crate::schema::words_counts_guarantees::table)
+                            .values(&word_record)
+                            .on_conflict((
+                                crate::schema::words_counts_guarantees::guarantee,
+                                crate::schema::words_counts_guarantees::namespace,
+                                crate::schema::words_counts_guarantees::kind,
+                                crate::schema::words_counts_guarantees::parent,
+                                crate::schema::words_counts_guarantees::lang,
+                                crate::schema::words_counts_guarantees::word,
+                            ))
+                            .do_update()
+                            .set(
+                                crate::schema::words_counts_guarantees::count
+                                    .eq(crate::schema::words_counts_guarantees::count
+                                        + excluded(crate::schema::words_counts_guarantees::count)),
+                            )
+                            .execute(conn)?;
+                    }
+
+                    Ok((recorded, rejected, replayed, exhausted))
+                })?;
+
+            // remember the nonces recorded in the table
+            for index in recorded.iter().chain(&replayed) {
+                let word = &words[*index].1;
+                nonces.insert(&word.metadata, policy.retention_date(&word.metadata));
+            }
+
+            for index in rejected {
+                errors.push(PutWordsError {
+                    index: index.try_into()?,
+                    error: IpdisError::Conflict(
+                        "duplicated word: the signature has already been used".into(),
+                    ),
+                });
+            }
+            for index in replayed {
+                errors.push(PutWordsError {
+                    index: index.try_into()?,
+                    error: nonce::replayed(),
+                });
+            }
+            for index in exhausted {
+                errors.push(PutWordsError {
+                    index: index.try_into()?,
+                    error: quota.exhausted_words(),
+                });
+            }
+            errors.sort_by_key(|error| error.index);
+            Ok(errors)
+        })
+        .await
+    }
+
+    async fn delete_word_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        let connection = self.connection.clone();
+        let guarantee = guarantee.copied();
+        let namespace = *namespace;
+        blocking(move || {
+            let guarantee = match guarantee {
+                Some(guarantee) => guarantee.to_string(),
+                None => return delete_word_all_guarantees(&connection, &namespace),
+            };
+
+            connection
+                .get()?
+                .transaction::<(), ::diesel::result::Error, _>(|conn| {
+                    ::diesel::delete(crate::schema::words::table)
+                        .filter(crate::schema::words::guarantee.eq(&guarantee))
+                        .filter(crate::schema::words::namespace.eq(namespace.to_string()))
+                        .execute(conn)
+                        .map(|_| ())?;
+
+                    // subtract the counts of the guarantee
+                    let counts: Vec<crate::models::words::WordCountGuarantee> =
+                        crate::schema::words_counts_guarantees::table
+                            .filter(
+                                crate::schema::words_counts_guarantees::guarantee.eq(&guarantee),
+                            )
+                            .filter(
+                                crate::schema::words_counts_guarantees::namespace
+                                    .eq(namespace.to_string()),
+                            )
+                            .get_results(conn)?;
+
+                    for count in &counts {
+                        ::diesel::update(crate::schema::words_counts::table)
+                            .filter(crate::schema::words_counts::namespace.eq(&count.namespace))
+                            .filter(crate::schema::words_counts::kind.eq(&count.kind))
+                            .filter(crate::schema::words_counts::parent.eq(&count.parent))
+                            .filter(crate::schema::words_counts::lang.eq(&count.lang))
+                            .filter(crate::schema::words_counts::word.eq(&count.word))
+                            .set(
+                                crate::schema::words_counts::count
+                                    .eq(crate::schema::words_counts::count - count.count),
+                            )
+                            .execute(conn)?;
+                    }
+
+                    ::diesel::delete(crate::schema::words_counts::table)
+                        .filter(crate::schema::words_counts::namespace.eq(namespace.to_string()))
+                        .filter(crate::schema::words_counts::count.le(0i64))
+                        .execute(conn)
+                        .map(|_| ())?;
+
+                    ::diesel::delete(crate::schema::words_counts_guarantees::table)
+                        .filter(crate::schema::words_counts_guarantees::guarantee.eq(&guarantee))
+                        .filter(
+                            crate::schema::words_counts_guarantees::namespace
+                                .eq(namespace.to_string()),
+                        )
+                        .execute(conn)
+                        .map(|_| ())?;
+
+                    Ok(())
+                })
+                .map_err(Into::into)
+        })
+        .await
+    }
+}
+
+fn delete_word_all_guarantees(
+    connection: &Pool<ConnectionManager<SqliteConnection>>,
+    namespace: &Hash,
+) -> Result<()> {
+    connection
+        .get()?
+        .transaction::<(), ::diesel::result::Error, _>(|conn| {
+            ::diesel::delete(crate::schema::words::table)
+                .filter(crate::schema::words::namespace.eq(namespace.to_string()))
+                .execute(conn)
+                .map(|_| ())?;
+
+            ::diesel::delete(crate::schema::words_counts::table)
+                .filter(crate::schema::words_counts::namespace.eq(namespace.to_string()))
+                .execute(conn)
+                .map(|_| ())?;
+
+            ::diesel::delete(crate::schema::words_counts_guarantees::table)
+                .filter(crate::schema::words_counts_guarantees::namespace.eq(namespace.to_string()))
+                .execute(conn)
+                .map(|_| ())?;
+
+            Ok(())
+        })
+        .map_err(Into::into)
+}
+
+fn nonce_record(
+    request: &GuaranteeSigned,
+    retention_date: ::ipis::core::chrono::NaiveDateTime,
+) -> crate::models::nonces::NewNonce {
+    crate::models::nonces::NewNonce {
+        guarantee: request.guarantee.account.to_string(),
+        nonce: request.data.nonce.0 .0.to_string(),
+        expiration_date: Some(retention_date),
+    }
+}
+
+/// Inserts the nonce only if it is not recorded yet.
+fn insert_nonce(
+    conn: &mut SqliteConnection,
+    record: &crate::models::nonces::NewNonce,
+) -> Result<bool> {
+    ::diesel::insert_into(crate::schema::nonces::table)
+        .values(record)
+        .on_conflict_do_nothing()
+        .execute(conn)
+        .map(|inserted| inserted > 0)
+        .map_err(Into::into)
+}
+
+fn cursor_id(cursor: &SignedCursor) -> Result<i32> {
+    let id = cursor.data.id();
+    id.try_into().or_else(|_| {
+        bail!(IpdisError::MalformedQuery(format!(
+            "malformed cursor: {id}"
+        )))
+    })
+}
+
+fn dyn_path_from_record(
+    record: crate::models::dyn_paths::DynPath,
+) -> Result<Data<GuarantorSigned, DynPath<Path>>> {
+    Ok(Data {
+        metadata: GuarantorSigned {
+            guarantor: Identity {
+                account: AccountRef {
+                    public_key: record.guarantor.parse()?,
+                },
+                signature: record.guarantor_signature.parse()?,
+            },
+            data: GuaranteeSigned {
+                guarantee: Identity {
+                    account: AccountRef {
+                        public_key: record.guarantee.parse()?,
+                    },
+                    signature: record.guarantee_signature.parse()?,
+                },
+                data: Metadata {
+                    nonce: Uuid(record.nonce.parse()?).into(),
+                    created_date: NaiveDateTime(record.created_date).to_utc(),
+                    expiration_date: record.expiration_date.map(|e| NaiveDateTime(e).to_utc()),
+                    guarantor: record.guarantor.parse()?,
+                    hash: record.hash.parse()?,
+                },
+            },
+        },
+        data: DynPath {
+            namespace: record.namespace.parse()?,
+            kind: record.kind.parse()?,
+            word: record.word.parse()?,
+            path: Path {
+                value: record.path.parse()?,
+                len: record.len.try_into()?,
+            },
+        },
+    })
+}
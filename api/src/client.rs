@@ -0,0 +1,6 @@
+use crate::storage::Storage;
+
+pub type IpdisClient = IpdisClientInner<::ipiis_api::client::IpiisClient>;
+
+pub type IpdisClientInner<IpiisClient> =
+    ::ipdis_api_common::client::IpdisClientInner<IpiisClient, Storage>;
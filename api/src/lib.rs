@@ -1,6 +1,5 @@
 pub extern crate ipdis_common as common;
 
+pub mod client;
 pub mod server;
-
-#[cfg(feature = "postgres")]
-pub use ipdis_api_postgres::*;
+pub mod storage;
@@ -1,6 +1,12 @@
-use std::sync::Arc;
+use std::{
+    future::{self, Future},
+    net::SocketAddr,
+    sync::Arc,
+    time::Duration,
+};
 
-use ipdis_common::Ipdis;
+use ipdis_api_common::env::infer_optional;
+use ipdis_common::{GuaranteeRole, Ipdis, IpdisError};
 use ipiis_api::{
     client::IpiisClient,
     common::{handle_external_call, Ipiis, ServerResult},
@@ -10,9 +16,17 @@ use ipis::{
     async_trait::async_trait,
     core::anyhow::{bail, Result},
     env::Infer,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::{TcpListener, TcpStream},
+    },
 };
 
-use crate::client::IpdisClientInner;
+use crate::{client::IpdisClientInner, storage::IpdisStorage};
+
+const DEFAULT_NONCE_SWEEP_INTERVAL_SECS: u64 = 60;
+const DEFAULT_SHUTDOWN_TIMEOUT_SECS: u64 = 30;
 
 pub struct IpdisServer {
     client: Arc<IpdisClientInner<IpiisServer>>,
@@ -46,42 +60,265 @@ impl<'a> Infer<'a> for IpdisServer {
     }
 }
 
+/// Defines the handlers which send their errors along with the archived [`IpdisError`]s,
+/// so that the remote clients can restore the same variants.
+macro_rules! define_handlers {
+    ( $( $io:ident => $handler:ident => $inner:ident , )* ) => {
+        impl IpdisServer {
+            $(
+                async fn $handler(
+                    client: &IpdisClientInner<IpiisServer>,
+                    req: ::ipdis_common::io::request::$io<'static>,
+                ) -> Result<::ipdis_common::io::response::$io<'static>> {
+                    Self::$inner(client, req)
+                        .await
+                        .map_err(|e| IpdisError::from(e).into_remote())
+                }
+            )*
+        }
+    };
+}
+
+define_handlers!(
+    HealthGet => handle_health_get => try_handle_health_get,
+    GuaranteePut => handle_guarantee_put => try_handle_guarantee_put,
+    GuaranteeGetMany => handle_guarantee_get_many => try_handle_guarantee_get_many,
+    GuaranteeDelete => handle_guarantee_delete => try_handle_guarantee_delete,
+    GuaranteeRevoke => handle_guarantee_revoke => try_handle_guarantee_revoke,
+    GuaranteeScopePut => handle_guarantee_scope_put => try_handle_guarantee_scope_put,
+    DynPathGet => handle_dyn_path_get => try_handle_dyn_path_get,
+    DynPathGetAt => handle_dyn_path_get_at => try_handle_dyn_path_get_at,
+    DynPathGetMany => handle_dyn_path_get_many => try_handle_dyn_path_get_many,
+    DynPathPut => handle_dyn_path_put => try_handle_dyn_path_put,
+    DynPathDelete => handle_dyn_path_delete => try_handle_dyn_path_delete,
+    WordGetMany => handle_word_get_many => try_handle_word_get_many,
+    WordCountGetMany => handle_word_count_get_many => try_handle_word_count_get_many,
+    NamespaceStatsGet => handle_namespace_stats_get => try_handle_namespace_stats_get,
+    QuotaGet => handle_quota_get => try_handle_quota_get,
+    WordPut => handle_word_put => try_handle_word_put,
+    WordPutMany => handle_word_put_many => try_handle_word_put_many,
+    WordDelete => handle_word_delete => try_handle_word_delete,
+);
+
 handle_external_call!(
     server: IpdisServer => IpdisClientInner<IpiisServer>,
-    name: run,
+    name: run_ipiis,
     request: ::ipdis_common::io => {
+        HealthGet => handle_health_get,
         GuaranteePut => handle_guarantee_put,
+        GuaranteeGetMany => handle_guarantee_get_many,
+        GuaranteeDelete => handle_guarantee_delete,
+        GuaranteeRevoke => handle_guarantee_revoke,
+        GuaranteeScopePut => handle_guarantee_scope_put,
         DynPathGet => handle_dyn_path_get,
+        DynPathGetAt => handle_dyn_path_get_at,
+        DynPathGetMany => handle_dyn_path_get_many,
         DynPathPut => handle_dyn_path_put,
+        DynPathDelete => handle_dyn_path_delete,
         WordGetMany => handle_word_get_many,
         WordCountGetMany => handle_word_count_get_many,
+        NamespaceStatsGet => handle_namespace_stats_get,
+        QuotaGet => handle_quota_get,
         WordPut => handle_word_put,
+        WordPutMany => handle_word_put_many,
+        WordDelete => handle_word_delete,
     },
 );
 
 impl IpdisServer {
-    async fn handle_guarantee_put(
+    /// Runs the server, along with the metrics listener if `ipdis_metrics_address` is given.
+    pub async fn run(&self) -> Result<()> {
+        self.run_until(future::pending()).await
+    }
+
+    /// Runs the server until the shutdown future is completed,
+    /// and then waits for the in-flight requests within `ipdis_shutdown_timeout_secs`.
+    ///
+    /// The storage is closed when the server is dropped.
+    /// The requests which are not drained in time are dropped and reported as an error.
+    pub async fn run_until(&self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let metrics_address: Option<SocketAddr> = infer_optional("ipdis_metrics_address")?;
+        let timeout: u64 =
+            infer_optional("ipdis_shutdown_timeout_secs")?.unwrap_or(DEFAULT_SHUTDOWN_TIMEOUT_SECS);
+        let sweep_interval: u64 = infer_optional("ipdis_api_nonce_sweep_interval_secs")?
+            .unwrap_or(DEFAULT_NONCE_SWEEP_INTERVAL_SECS);
+        if sweep_interval == 0 {
+            bail!("malformed interval: the nonce sweep interval should be positive")
+        }
+        let metrics = match metrics_address {
+            Some(address) => {
+                let listener = match TcpListener::bind(address).await {
+                    Ok(listener) => listener,
+                    Err(e) => bail!("failed to bind the metrics listener to {address}: {e}"),
+                };
+                Some(tokio::spawn(Self::serve_metrics(
+                    self.client.clone(),
+                    listener,
+                )))
+            }
+            None => None,
+        };
+        let sweep = tokio::spawn(Self::sweep_nonces(
+            self.client.clone(),
+            Duration::from_secs(sweep_interval),
+        ));
+
+        // stop accepting the connections on shutdown
+        tokio::select! {
+            () = self.run_ipiis() => {}
+            () = shutdown => {}
+        }
+
+        // drain the in-flight requests, rejecting the new ones
+        let drain = self.client.drain();
+        drain.close();
+        let drained = tokio::time::timeout(Duration::from_secs(timeout), drain.wait()).await;
+
+        if let Some(metrics) = metrics {
+            metrics.abort();
+        }
+        sweep.abort();
+        if drained.is_err() {
+            bail!(
+                "shutdown timeout: dropping {} in-flight requests",
+                drain.in_flight(),
+            )
+        }
+        Ok(())
+    }
+
+    /// Forgets the expired nonces periodically, rather than on every signed request.
+    ///
+    /// The failed sweeps are counted as failed `NonceSweep` calls, and retried on the next tick.
+    async fn sweep_nonces(client: Arc<IpdisClientInner<IpiisServer>>, interval: Duration) {
+        let mut interval = tokio::time::interval(interval);
+        loop {
+            interval.tick().await;
+
+            let call = client.metrics().start("NonceSweep");
+            if client.storage().sweep_nonces().await.is_ok() {
+                call.succeed();
+            }
+        }
+    }
+
+    /// Responds to every HTTP request with the metrics in the Prometheus text format.
+    ///
+    /// The connections failed to be read or written are counted in the metrics.
+    async fn serve_metrics(
+        client: Arc<IpdisClientInner<IpiisServer>>,
+        listener: TcpListener,
+    ) -> Result<()> {
+        loop {
+            let (mut stream, _) = listener.accept().await?;
+            let client = client.clone();
+
+            tokio::spawn(async move {
+                let body = client.metrics().render(client.storage().pool_state());
+                if Self::respond_metrics(&mut stream, &body).await.is_err() {
+                    client.metrics().add_connection_error();
+                }
+            });
+        }
+    }
+
+    async fn respond_metrics(stream: &mut TcpStream, body: &str) -> ::std::io::Result<()> {
+        // the request line and the headers are not inspected
+        let mut buf = [0; 1024];
+        stream.read(&mut buf).await?;
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len(),
+        );
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    async fn try_handle_health_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::HealthGet<'static>,
+    ) -> Result<::ipdis_common::io::response::HealthGet<'static>> {
+        let call = client.metrics().start("HealthGet");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        // the nonce is not recorded, so that the storage failures are reported as the health
+        client.policy().ensure_alive(&sign_as_guarantee.metadata)?;
+
+        // handle data (anyone can check it)
+        let health = client.get_health().await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let health = server.sign_as_guarantor(server.sign_owned(*server.account_ref(), health)?)?;
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::HealthGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            health: ::ipis::stream::DynStream::Owned(health),
+        })
+    }
+
+    async fn try_handle_guarantee_put(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::GuaranteePut<'static>,
     ) -> Result<::ipdis_common::io::response::GuaranteePut<'static>> {
+        let call = client.metrics().start("GuaranteePut");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
         let sign_as_guarantor = &sign_as_guarantee.data;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
         // ensure registered (only the guarantor)
         let guarantor = &sign_as_guarantor.metadata.guarantor.account;
         client
-            .ensure_registered(guarantor, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantor,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::Admin,
+                None,
+            )
             .await?;
         client
-            .ensure_registered(guarantor, &sign_as_guarantor.metadata.guarantor.account)
+            .ensure_registered(
+                guarantor,
+                &sign_as_guarantor.metadata.guarantor.account,
+                GuaranteeRole::Admin,
+                None,
+            )
             .await?;
 
-        // either one can request it
-        if sign_as_guarantee.metadata.guarantee.account
-            != sign_as_guarantor.metadata.guarantee.account
-        {
-            bail!("Only the guarantee can perform it")
+        // either the guarantee itself or an admin can request it
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        let target = &sign_as_guarantor.metadata.guarantee.account;
+        if guarantee != target {
+            client
+                .ensure_registered(
+                    guarantee,
+                    &sign_as_guarantee.metadata.guarantor,
+                    GuaranteeRole::Admin,
+                    None,
+                )
+                .await?;
+
+            // the admin cannot give a wider scope than its own
+            let scope = client.get_scope(target, guarantor).await?;
+            client.ensure_grantable(guarantee, &scope).await?;
         }
 
         // handle data
@@ -92,23 +329,235 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::GuaranteePut {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
         })
     }
 
-    async fn handle_dyn_path_get(
+    async fn try_handle_guarantee_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::GuaranteeGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::GuaranteeGetMany<'static>> {
+        let call = client.metrics().start("GuaranteeGetMany");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // unpack data
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        let guarantor = &sign_as_guarantee.metadata.guarantor;
+        let query = sign_as_guarantee.data;
+
+        // the guarantee can inspect its own registration without being registered
+        if query.guarantee.as_ref() == Some(guarantee) {
+            client
+                .ensure_registered(guarantor, guarantor, GuaranteeRole::ReadOnly, None)
+                .await?;
+        } else {
+            client
+                .ensure_registered(guarantee, guarantor, GuaranteeRole::ReadOnly, None)
+                .await?;
+
+            // only the guarantor can inspect the others
+            if guarantee != guarantor {
+                client.metrics().add_auth_failure();
+                bail!(IpdisError::Unauthenticated(
+                    "Only the guarantee itself or the guarantor can perform it".into(),
+                ))
+            }
+        }
+
+        // handle data
+        let guarantees = client.get_guarantee_many_unchecked(&query).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::GuaranteeGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            guarantees: ::ipis::stream::DynStream::Owned(guarantees),
+        })
+    }
+
+    async fn try_handle_guarantee_delete(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::GuaranteeDelete<'static>,
+    ) -> Result<::ipdis_common::io::response::GuaranteeDelete<'static>> {
+        let call = client.metrics().start("GuaranteeDelete");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        let guarantor = &sign_as_guarantee.metadata.guarantor;
+        client
+            .ensure_registered(guarantee, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // unpack data
+        let target = sign_as_guarantee.data;
+
+        // either the guarantee itself or the guarantor can request it
+        if guarantee != &target && guarantee != guarantor {
+            client.metrics().add_auth_failure();
+            bail!(IpdisError::Unauthenticated(
+                "Only the guarantee itself or the guarantor can perform it".into(),
+            ))
+        }
+
+        // handle data
+        client.delete_guarantee_unchecked(&target).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::GuaranteeDelete {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn try_handle_guarantee_revoke(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::GuaranteeRevoke<'static>,
+    ) -> Result<::ipdis_common::io::response::GuaranteeRevoke<'static>> {
+        let call = client.metrics().start("GuaranteeRevoke");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        let guarantor = &sign_as_guarantee.metadata.guarantor;
+        client
+            .ensure_registered(guarantee, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // either the guarantee itself or the guarantor can request it
+        if guarantee != &sign_as_guarantee.data && guarantee != guarantor {
+            client.metrics().add_auth_failure();
+            bail!(IpdisError::Unauthenticated(
+                "Only the guarantee itself or the guarantor can perform it".into(),
+            ))
+        }
+
+        // handle data
+        client
+            .revoke_guarantee_unchecked(&sign_as_guarantee)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::GuaranteeRevoke {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn try_handle_guarantee_scope_put(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::GuaranteeScopePut<'static>,
+    ) -> Result<::ipdis_common::io::response::GuaranteeScopePut<'static>> {
+        let call = client.metrics().start("GuaranteeScopePut");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered (only an admin)
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::Admin,
+                None,
+            )
+            .await?;
+
+        // the admin cannot give a wider scope than its own
+        client
+            .ensure_grantable(guarantee, &sign_as_guarantee.data)
+            .await?;
+
+        // handle data
+        client
+            .set_guarantee_scope_unchecked(&sign_as_guarantee)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::GuaranteeScopePut {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn try_handle_dyn_path_get(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::DynPathGet<'static>,
     ) -> Result<::ipdis_common::io::response::DynPathGet<'static>> {
+        let call = client.metrics().start("DynPathGet");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
         // ensure registered
         let guarantee = &sign_as_guarantee.metadata.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.namespace),
+            )
             .await?;
 
         // unpack data
@@ -124,6 +573,7 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::DynPathGet {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
@@ -131,17 +581,126 @@ impl IpdisServer {
         })
     }
 
-    async fn handle_dyn_path_put(
+    async fn try_handle_dyn_path_get_at(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathGetAt<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGetAt<'static>> {
+        let call = client.metrics().start("DynPathGetAt");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.path.namespace),
+            )
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data;
+
+        // handle data
+        let path = client
+            .get_dyn_path_at_unchecked(Some(guarantee), &query.path, &query.timestamp)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::DynPathGetAt {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            path: ::ipis::stream::DynStream::Owned(path),
+        })
+    }
+
+    async fn try_handle_dyn_path_get_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathGetMany<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathGetMany<'static>> {
+        let call = client.metrics().start("DynPathGetMany");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.path.namespace),
+            )
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data;
+
+        // handle data
+        let page = client
+            .get_dyn_path_many_unchecked(Some(guarantee), &query)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::DynPathGetMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            paths: ::ipis::stream::DynStream::Owned(page.items),
+            cursor: ::ipis::stream::DynStream::Owned(page.cursor),
+        })
+    }
+
+    async fn try_handle_dyn_path_put(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::DynPathPut<'static>,
     ) -> Result<::ipdis_common::io::response::DynPathPut<'static>> {
+        let call = client.metrics().start("DynPathPut");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        // the nonce is recorded along with the record
+        client.policy().ensure_recent(&sign_as_guarantee.metadata)?;
+
         // ensure registered
         let guarantee = &sign_as_guarantee.metadata.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadWrite,
+                Some(&sign_as_guarantee.data.namespace),
+            )
             .await?;
 
         // handle data
@@ -152,30 +711,90 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::DynPathPut {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
         })
     }
 
-    async fn handle_word_get_many(
+    async fn try_handle_dyn_path_delete(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::DynPathDelete<'static>,
+    ) -> Result<::ipdis_common::io::response::DynPathDelete<'static>> {
+        let call = client.metrics().start("DynPathDelete");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadWrite,
+                Some(&sign_as_guarantee.data.namespace),
+            )
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data;
+
+        // handle data (only the records signed by the guarantee)
+        client
+            .delete_dyn_path_all_unchecked(Some(guarantee), &query.namespace)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::DynPathDelete {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
+
+    async fn try_handle_word_get_many(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::WordGetMany<'static>,
     ) -> Result<::ipdis_common::io::response::WordGetMany<'static>> {
+        let call = client.metrics().start("WordGetMany");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
         // ensure registered
         let guarantee = &sign_as_guarantee.metadata.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.word.namespace),
+            )
             .await?;
 
         // unpack data
         let query = sign_as_guarantee.data;
 
         // handle data
-        let words = client
+        let page = client
             .get_word_many_unchecked(Some(guarantee), &query)
             .await?;
 
@@ -184,31 +803,46 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::WordGetMany {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
-            words: ::ipis::stream::DynStream::Owned(words),
+            words: ::ipis::stream::DynStream::Owned(page.items),
+            cursor: ::ipis::stream::DynStream::Owned(page.cursor),
         })
     }
 
-    async fn handle_word_count_get_many(
+    async fn try_handle_word_count_get_many(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::WordCountGetMany<'static>,
     ) -> Result<::ipdis_common::io::response::WordCountGetMany<'static>> {
+        let call = client.metrics().start("WordCountGetMany");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
         // ensure registered
         let guarantee = &sign_as_guarantee.metadata.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.word.namespace),
+            )
             .await?;
 
         // unpack data
         let query = sign_as_guarantee.data;
 
         // handle data
-        let counts = client
+        let page = client
             .get_word_count_many_unchecked(Some(guarantee), &query)
             .await?;
 
@@ -217,24 +851,129 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::WordCountGetMany {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
-            counts: ::ipis::stream::DynStream::Owned(counts),
+            counts: ::ipis::stream::DynStream::Owned(page.items),
+            cursor: ::ipis::stream::DynStream::Owned(page.cursor),
+        })
+    }
+
+    async fn try_handle_namespace_stats_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::NamespaceStatsGet<'static>,
+    ) -> Result<::ipdis_common::io::response::NamespaceStatsGet<'static>> {
+        let call = client.metrics().start("NamespaceStatsGet");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                Some(&sign_as_guarantee.data.namespace),
+            )
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data;
+
+        // handle data
+        let stats = client
+            .get_namespace_stats_unchecked(&query.namespace)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::NamespaceStatsGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            stats: ::ipis::stream::DynStream::Owned(stats),
         })
     }
 
-    async fn handle_word_put(
+    async fn try_handle_quota_get(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::QuotaGet<'static>,
+    ) -> Result<::ipdis_common::io::response::QuotaGet<'static>> {
+        let call = client.metrics().start("QuotaGet");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadOnly,
+                None,
+            )
+            .await?;
+
+        // handle data
+        let quota = client.get_quota_unchecked(guarantee).await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::QuotaGet {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            quota: ::ipis::stream::DynStream::Owned(quota),
+        })
+    }
+
+    async fn try_handle_word_put(
         client: &IpdisClientInner<IpiisServer>,
         req: ::ipdis_common::io::request::WordPut<'static>,
     ) -> Result<::ipdis_common::io::response::WordPut<'static>> {
+        let call = client.metrics().start("WordPut");
+        let _request = client.drain().enter()?;
+
         // unpack sign
         let sign_as_guarantee = req.__sign.into_owned().await?;
 
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        // the nonce is recorded along with the record
+        client.policy().ensure_recent(&sign_as_guarantee.metadata)?;
+
         // ensure registered
         let guarantee = &sign_as_guarantee.metadata.guarantee.account;
         client
-            .ensure_registered(guarantee, &sign_as_guarantee.metadata.guarantor)
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadWrite,
+                Some(&sign_as_guarantee.data.key.namespace),
+            )
             .await?;
 
         // unpack data
@@ -250,9 +989,125 @@ impl IpdisServer {
         let sign = server.sign_as_guarantor(sign_as_guarantee)?;
 
         // pack data
+        call.succeed();
         Ok(::ipdis_common::io::response::WordPut {
             __lifetime: Default::default(),
             __sign: ::ipis::stream::DynStream::Owned(sign),
         })
     }
+
+    async fn try_handle_word_put_many(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordPutMany<'static>,
+    ) -> Result<::ipdis_common::io::response::WordPutMany<'static>> {
+        let call = client.metrics().start("WordPutMany");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadWrite,
+                None,
+            )
+            .await?;
+
+        // unpack data
+        let words = req.words.into_owned().await?;
+        if words.len() != sign_as_guarantee.data.count as usize {
+            bail!(IpdisError::MalformedQuery(
+                "malformed words: the count is not matched".into(),
+            ))
+        }
+
+        // ensure the namespaces are in the scope
+        let mut namespaces = vec![];
+        for (_, word) in &words {
+            let namespace = &word.data.key.namespace;
+            if !namespaces.contains(&namespace) {
+                client
+                    .ensure_registered(
+                        guarantee,
+                        &sign_as_guarantee.metadata.guarantor,
+                        GuaranteeRole::ReadWrite,
+                        Some(namespace),
+                    )
+                    .await?;
+                namespaces.push(namespace);
+            }
+        }
+
+        // handle data
+        let errors = client
+            .put_word_many_unchecked(Some(guarantee), &words)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::WordPutMany {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+            errors: ::ipis::stream::DynStream::Owned(errors),
+        })
+    }
+
+    async fn try_handle_word_delete(
+        client: &IpdisClientInner<IpiisServer>,
+        req: ::ipdis_common::io::request::WordDelete<'static>,
+    ) -> Result<::ipdis_common::io::response::WordDelete<'static>> {
+        let call = client.metrics().start("WordDelete");
+        let _request = client.drain().enter()?;
+
+        // unpack sign
+        let sign_as_guarantee = req.__sign.into_owned().await?;
+
+        // verify sign
+        client.ensure_verified(&sign_as_guarantee)?;
+        client.ensure_rate(&sign_as_guarantee.metadata.guarantee.account, call.name())?;
+        client.ensure_fresh(&sign_as_guarantee.metadata).await?;
+
+        // ensure registered
+        let guarantee = &sign_as_guarantee.metadata.guarantee.account;
+        client
+            .ensure_registered(
+                guarantee,
+                &sign_as_guarantee.metadata.guarantor,
+                GuaranteeRole::ReadWrite,
+                Some(&sign_as_guarantee.data.namespace),
+            )
+            .await?;
+
+        // unpack data
+        let query = sign_as_guarantee.data;
+
+        // handle data (only the records signed by the guarantee)
+        client
+            .delete_word_all_unchecked(Some(guarantee), &query.namespace)
+            .await?;
+
+        // sign data
+        let server: &IpiisServer = client.as_ref();
+        let sign = server.sign_as_guarantor(sign_as_guarantee)?;
+
+        // pack data
+        call.succeed();
+        Ok(::ipdis_common::io::response::WordDelete {
+            __lifetime: Default::default(),
+            __sign: ::ipis::stream::DynStream::Owned(sign),
+        })
+    }
 }
@@ -0,0 +1,280 @@
+pub use ipdis_api_common::storage::{IpdisStorage, PoolState};
+
+use ipdis_api_common::{
+    policy::RequestPolicy,
+    quota::{QuotaPolicy, Usage},
+};
+#[cfg(feature = "memory")]
+pub use ipdis_api_memory::MemoryStorage;
+#[cfg(feature = "postgres")]
+pub use ipdis_api_postgres::PostgresStorage;
+#[cfg(feature = "sled")]
+pub use ipdis_api_sled::SledStorage;
+#[cfg(feature = "sqlite")]
+pub use ipdis_api_sqlite::SqliteStorage;
+
+use ipdis_common::{
+    GetDynPaths, GetGuarantees, GetWords, GetWordsCounts, GetWordsCountsOutput, GuaranteeScope,
+    NamespaceStats, Page, PutWordsError,
+};
+use ipis::{
+    async_trait::async_trait,
+    core::{
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        anyhow::{bail, Result},
+        chrono::{NaiveDateTime, Utc},
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
+    env,
+    path::{DynPath, Path},
+    word::WordHash,
+};
+
+#[cfg(not(any(
+    feature = "memory",
+    feature = "postgres",
+    feature = "sled",
+    feature = "sqlite",
+)))]
+compile_error!("at least one storage feature should be enabled");
+
+#[cfg(feature = "postgres")]
+const DEFAULT_STORAGE: &str = "postgres";
+#[cfg(all(not(feature = "postgres"), feature = "sqlite"))]
+const DEFAULT_STORAGE: &str = "sqlite";
+#[cfg(all(not(any(feature = "postgres", feature = "sqlite")), feature = "sled"))]
+const DEFAULT_STORAGE: &str = "sled";
+#[cfg(all(
+    not(any(feature = "postgres", feature = "sled", feature = "sqlite")),
+    feature = "memory",
+))]
+const DEFAULT_STORAGE: &str = "memory";
+
+/// A storage which is selected by `ipdis_api_storage` on runtime.
+pub enum Storage {
+    #[cfg(feature = "memory")]
+    Memory(MemoryStorage),
+    #[cfg(feature = "postgres")]
+    Postgres(PostgresStorage),
+    #[cfg(feature = "sled")]
+    Sled(SledStorage),
+    #[cfg(feature = "sqlite")]
+    Sqlite(SqliteStorage),
+}
+
+macro_rules! dispatch {
+    ( $self:ident . $method:ident ( $( $arg:expr ),* ) ) => {
+        match $self {
+            #[cfg(feature = "memory")]
+            Self::Memory(storage) => storage.$method( $( $arg ),* ).await,
+            #[cfg(feature = "postgres")]
+            Self::Postgres(storage) => storage.$method( $( $arg ),* ).await,
+            #[cfg(feature = "sled")]
+            Self::Sled(storage) => storage.$method( $( $arg ),* ).await,
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => storage.$method( $( $arg ),* ).await,
+        }
+    };
+}
+
+/// Opens the storage selected by `ipdis_api_storage` with the given constructor.
+macro_rules! infer_storage {
+    ( $method:ident ) => {{
+        let kind: String =
+            env::infer("ipdis_api_storage").unwrap_or_else(|_| DEFAULT_STORAGE.to_string());
+
+        match kind.as_str() {
+            #[cfg(feature = "memory")]
+            "memory" => MemoryStorage::$method().await.map(Self::Memory),
+            #[cfg(feature = "postgres")]
+            "postgres" => PostgresStorage::$method().await.map(Self::Postgres),
+            #[cfg(feature = "sled")]
+            "sled" => SledStorage::$method().await.map(Self::Sled),
+            #[cfg(feature = "sqlite")]
+            "sqlite" => SqliteStorage::$method().await.map(Self::Sqlite),
+            _ => bail!("unsupported storage: {kind}"),
+        }
+    }};
+}
+
+#[async_trait]
+impl IpdisStorage for Storage {
+    async fn try_infer() -> Result<Self> {
+        infer_storage!(try_infer)
+    }
+
+    async fn try_infer_verify_only() -> Result<Self> {
+        infer_storage!(try_infer_verify_only)
+    }
+
+    async fn get_usage(&self, guarantee: &AccountRef) -> Result<Usage> {
+        dispatch!(self.get_usage(guarantee))
+    }
+
+    fn pool_state(&self) -> Option<PoolState> {
+        match self {
+            #[cfg(feature = "memory")]
+            Self::Memory(storage) => storage.pool_state(),
+            #[cfg(feature = "postgres")]
+            Self::Postgres(storage) => storage.pool_state(),
+            #[cfg(feature = "sled")]
+            Self::Sled(storage) => storage.pool_state(),
+            #[cfg(feature = "sqlite")]
+            Self::Sqlite(storage) => storage.pool_state(),
+        }
+    }
+
+    async fn get_pending_migrations(&self) -> Result<u32> {
+        dispatch!(self.get_pending_migrations())
+    }
+
+    async fn contains_guarantee(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<bool> {
+        dispatch!(self.contains_guarantee(guarantee, guarantor))
+    }
+
+    async fn put_guarantee(&self, guarantee: &Data<GuarantorSigned, AccountRef>) -> Result<()> {
+        dispatch!(self.put_guarantee(guarantee))
+    }
+
+    async fn get_guarantee_many(
+        &self,
+        guarantor: &AccountRef,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        dispatch!(self.get_guarantee_many(guarantor, query))
+    }
+
+    async fn delete_guarantee_all(&self, guarantee: &AccountRef) -> Result<()> {
+        dispatch!(self.delete_guarantee_all(guarantee))
+    }
+
+    async fn get_guarantee_revocation_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, AccountRef>>> {
+        dispatch!(self.get_guarantee_revocation_latest(guarantee, guarantor))
+    }
+
+    async fn put_guarantee_revocation(
+        &self,
+        revocation: &Data<GuarantorSigned, AccountRef>,
+    ) -> Result<()> {
+        dispatch!(self.put_guarantee_revocation(revocation))
+    }
+
+    async fn get_guarantee_scope_latest(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+    ) -> Result<Option<Data<GuarantorSigned, GuaranteeScope>>> {
+        dispatch!(self.get_guarantee_scope_latest(guarantee, guarantor))
+    }
+
+    async fn put_guarantee_scope(
+        &self,
+        scope: &Data<GuarantorSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        dispatch!(self.put_guarantee_scope(scope))
+    }
+
+    async fn put_nonce(
+        &self,
+        request: &GuaranteeSigned,
+        retention_date: NaiveDateTime,
+    ) -> Result<bool> {
+        dispatch!(self.put_nonce(request, retention_date))
+    }
+
+    async fn sweep_nonces(&self) -> Result<()> {
+        dispatch!(self.sweep_nonces())
+    }
+
+    async fn get_dyn_path(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        dispatch!(self.get_dyn_path(guarantee, guarantor, path))
+    }
+
+    async fn get_dyn_path_at(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        path: &DynPath<()>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Option<Data<GuarantorSigned, DynPath<Path>>>> {
+        dispatch!(self.get_dyn_path_at(guarantee, guarantor, path, timestamp))
+    }
+
+    async fn get_dyn_path_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>>> {
+        dispatch!(self.get_dyn_path_many(guarantee, guarantor, query))
+    }
+
+    async fn put_dyn_path(
+        &self,
+        path: &Data<GuarantorSigned, DynPath<Path>>,
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<()> {
+        dispatch!(self.put_dyn_path(path, quota, policy))
+    }
+
+    async fn delete_dyn_path_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        dispatch!(self.delete_dyn_path_all(guarantee, namespace))
+    }
+
+    async fn get_word_many(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>>> {
+        dispatch!(self.get_word_many(guarantee, guarantor, query))
+    }
+
+    async fn get_word_count_many(
+        &self,
+        guarantee: &AccountRef,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput>> {
+        dispatch!(self.get_word_count_many(guarantee, query))
+    }
+
+    async fn get_namespace_stats(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        dispatch!(self.get_namespace_stats(namespace))
+    }
+
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuarantorSigned, WordHash>)],
+        quota: &QuotaPolicy,
+        policy: &RequestPolicy,
+    ) -> Result<Vec<PutWordsError>> {
+        dispatch!(self.put_word_many(words, quota, policy))
+    }
+
+    async fn delete_word_all(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        dispatch!(self.delete_word_all(guarantee, namespace))
+    }
+}
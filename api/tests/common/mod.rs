@@ -0,0 +1,135 @@
+#![allow(dead_code)]
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+};
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::{Ipdis, KIND},
+    server::IpdisServer,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis, server::IpiisServer};
+use ipis::{
+    core::value::text::Text,
+    env::Infer,
+    path::Path,
+    tokio,
+    word::{Word, WordHash, WordKey},
+};
+
+/// Deploys a server on the port, and registers a new account which sends the requests to it.
+pub async fn deploy_server(name: &str, port: u16) -> (Arc<IpdisServer>, IpiisClient) {
+    // deploy a server
+    let server: Arc<IpdisServer> = {
+        ::std::env::set_var(
+            "ipiis_router_db",
+            format!("/tmp/ipdis-api-test-{name}-server-ipiis-router-db"),
+        );
+        IpdisServer::genesis(port).await.unwrap().into()
+    };
+    tokio::spawn({
+        let server = server.clone();
+        async move { server.run().await }
+    });
+
+    // create a client
+    let client = connect_client(&format!("{name}-client"), &server, port).await;
+
+    // register the client as guarantee
+    let ipiis: &IpiisServer = (*server).as_ref();
+    let guarantee = ipiis
+        .sign_as_guarantor(
+            client
+                .sign_owned(*ipiis.account_ref(), *client.account_ref())
+                .unwrap(),
+        )
+        .unwrap();
+    server.add_guarantee_unchecked(&guarantee).await.unwrap();
+
+    (server, client)
+}
+
+/// Creates a new account which sends the requests to the server on the port.
+pub async fn connect_client(name: &str, server: &IpdisServer, port: u16) -> IpiisClient {
+    let server_account = {
+        let server: &IpiisServer = server.as_ref();
+        *server.account_ref()
+    };
+
+    let client = {
+        ::std::env::set_var(
+            "ipiis_router_db",
+            format!("/tmp/ipdis-api-test-{name}-ipiis-router-db"),
+        );
+        IpiisClient::genesis(None).await.unwrap()
+    };
+    client
+        .set_account_primary(KIND.as_ref(), &server_account)
+        .await
+        .unwrap();
+    client
+        .set_address(
+            KIND.as_ref(),
+            &server_account,
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, port)),
+        )
+        .await
+        .unwrap();
+    client
+}
+
+/// Creates a new account, and registers it as a guarantee of the client.
+pub async fn create_guarantee(client: &IpdisClient, name: &str) -> IpiisClient {
+    let ipiis: &IpiisClient = client.as_ref();
+    let guarantor = *ipiis.account_ref();
+
+    // create a client
+    let guarantee = {
+        ::std::env::set_var(
+            "ipiis_router_db",
+            format!("/tmp/ipdis-api-test-{name}-ipiis-router-db"),
+        );
+        IpiisClient::genesis(None).await.unwrap()
+    };
+
+    // register the client as guarantee
+    add_guarantee(client, &guarantee).await;
+
+    guarantee
+}
+
+/// Registers the account as a guarantee of the client.
+pub async fn add_guarantee(client: &IpdisClient, guarantee: &IpiisClient) {
+    let ipiis: &IpiisClient = client.as_ref();
+    let guarantor = *ipiis.account_ref();
+
+    let record = ipiis
+        .sign_as_guarantor(
+            guarantee
+                .sign_owned(guarantor, *guarantee.account_ref())
+                .unwrap(),
+        )
+        .unwrap();
+    client.add_guarantee_unchecked(&record).await.unwrap();
+}
+
+/// Creates a sample word to be stored, whose kind is the same as the namespace.
+pub fn create_word(namespace: &str, text: &str) -> WordHash {
+    Word {
+        key: WordKey {
+            namespace: namespace.to_string(),
+            text: Text::with_en_us(text),
+        },
+        kind: namespace.to_string(),
+        relpath: true,
+        path: Path {
+            value: "bafkreihm64a7oj6z4llxysvetldpxpgjs4tyvsqbbpo65olbyegpktkdli"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    }
+    .into()
+}
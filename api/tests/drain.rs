@@ -0,0 +1,34 @@
+use std::time::Duration;
+
+use ipdis_api::common::IpdisError;
+use ipdis_api_common::drain::Drain;
+use ipis::tokio;
+
+#[tokio::test]
+async fn test_drain() {
+    let drain = Drain::default();
+
+    // admit a request
+    let request = drain.enter().unwrap();
+    assert_eq!(drain.in_flight(), 1);
+
+    // ensure that the new requests are rejected while draining
+    drain.close();
+    assert!(matches!(
+        drain.enter().map(|_| ()).map_err(IpdisError::from),
+        Err(IpdisError::Unavailable(_)),
+    ));
+    assert_eq!(drain.in_flight(), 1);
+
+    // ensure that the in-flight request is waited
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), drain.wait())
+            .await
+            .is_err()
+    );
+
+    drop(request);
+    tokio::time::timeout(Duration::from_millis(100), drain.wait())
+        .await
+        .unwrap();
+}
@@ -1,13 +1,28 @@
+mod common;
+
 use ipdis_api::client::IpdisClient;
-use ipdis_common::Ipdis;
+use ipdis_common::{DeleteDynPaths, GetDynPaths, Ipdis, IpdisError};
 use ipiis_api::{client::IpiisClient, common::Ipiis};
 use ipis::{
-    core::value::hash::Hash,
+    core::{
+        account::GuaranteeSigned,
+        anyhow::Result,
+        chrono::Utc,
+        data::Data,
+        value::{chrono::DateTime, hash::Hash},
+    },
     env::Infer,
     path::{DynPath, Path},
     tokio,
 };
 
+fn assert_not_found<T>(result: Result<T>) {
+    assert!(matches!(
+        result.map_err(IpdisError::from),
+        Err(IpdisError::NotFound(_)),
+    ))
+}
+
 #[tokio::test]
 async fn test_create() {
     // create a client
@@ -38,7 +53,7 @@ async fn test_create() {
 
     // cleanup test data
     client
-        .delete_dyn_path_all_unchecked(&dyn_path.kind)
+        .delete_dyn_path_all_unchecked(None, &dyn_path.kind)
         .await
         .unwrap();
 
@@ -52,7 +67,6 @@ async fn test_create() {
     let dyn_path_from_ipdis = client
         .get_dyn_path_unchecked(None, &dyn_path.remove_path())
         .await
-        .unwrap()
         .unwrap();
     // FIXME: precision issue (postgres != rust chrono)
     // assert_eq!(&dyn_path_from_ipdis.metadata.data, &dyn_path.metadata,);
@@ -60,7 +74,268 @@ async fn test_create() {
 
     // cleanup test data
     client
-        .delete_dyn_path_all_unchecked(&dyn_path.namespace)
+        .delete_dyn_path_all_unchecked(None, &dyn_path.namespace)
         .await
         .unwrap()
 }
+
+/// Puts the versions of a dynamic path in order, with the instants around each of them.
+async fn put_versions(
+    client: &IpdisClient,
+    name: &str,
+) -> (
+    Vec<Data<GuaranteeSigned, DynPath<Path>>>,
+    Vec<DateTime<Utc>>,
+) {
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str(name),
+        kind: Hash::with_str(name),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "bafkreihm64a7oj6z4llxysvetldpxpgjs4tyvsqbbpo65olbyegpktkdli"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(None, &dyn_path.namespace)
+        .await
+        .unwrap();
+
+    // take the instants from the records signed but not put
+    let mut versions = vec![];
+    let mut instants = vec![];
+    for len in 0..3 {
+        tokio::time::sleep(::std::time::Duration::from_millis(10)).await;
+        let instant = ipiis.sign_owned(*account, dyn_path).unwrap();
+        instants.push(instant.metadata.created_date);
+
+        tokio::time::sleep(::std::time::Duration::from_millis(10)).await;
+        let version = DynPath {
+            path: Path {
+                len: 13 + len,
+                ..dyn_path.path
+            },
+            ..dyn_path
+        };
+        let version = ipiis.sign_owned(*account, version).unwrap();
+        client.put_dyn_path_unchecked(&version).await.unwrap();
+        versions.push(version);
+    }
+
+    tokio::time::sleep(::std::time::Duration::from_millis(10)).await;
+    let instant = ipiis.sign_owned(*account, dyn_path).unwrap();
+    instants.push(instant.metadata.created_date);
+    (versions, instants)
+}
+
+#[tokio::test]
+async fn test_history() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // put the versions of a path
+    let (versions, _) = put_versions(&client, "ipdis-api-postgres-test-history").await;
+    let path = versions[0].remove_path();
+    let query = GetDynPaths {
+        path,
+        since: None,
+        until: None,
+        cursor: None,
+        start_index: 0,
+        end_index: 10,
+    };
+
+    // get the history of the path, the latest first
+    let history = client
+        .get_dyn_path_many_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(
+        history
+            .items
+            .iter()
+            .map(|item| item.data)
+            .collect::<Vec<_>>(),
+        versions
+            .iter()
+            .rev()
+            .map(|version| version.data)
+            .collect::<Vec<_>>(),
+    );
+    assert!(history.cursor.is_none());
+
+    // get the history within the inclusive bounds
+    let middle = versions[1].metadata.created_date;
+    for (since, until, expected) in [
+        (Some(middle), None, vec![&versions[2], &versions[1]]),
+        (None, Some(middle), vec![&versions[1], &versions[0]]),
+        (Some(middle), Some(middle), vec![&versions[1]]),
+    ] {
+        let query = GetDynPaths {
+            since,
+            until,
+            ..query
+        };
+        let history = client
+            .get_dyn_path_many_unchecked(None, &query)
+            .await
+            .unwrap();
+        assert_eq!(
+            history
+                .items
+                .iter()
+                .map(|item| item.data)
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|version| version.data)
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    // paginate the history with the cursor
+    let query = GetDynPaths {
+        end_index: 2,
+        ..query
+    };
+    let page = client
+        .get_dyn_path_many_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 2);
+    assert_eq!(&page.items[0].data, &versions[2].data);
+    assert_eq!(&page.items[1].data, &versions[1].data);
+
+    let query = GetDynPaths {
+        cursor: page.cursor,
+        ..query
+    };
+    let page = client
+        .get_dyn_path_many_unchecked(None, &query)
+        .await
+        .unwrap();
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(&page.items[0].data, &versions[0].data);
+    assert!(page.cursor.is_none());
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(None, &path.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_at() {
+    // create a client
+    let client = IpdisClient::infer().await;
+
+    // put the versions of a path
+    let (versions, instants) = put_versions(&client, "ipdis-api-postgres-test-get-at").await;
+    let path = versions[0].remove_path();
+
+    // ensure that nothing is resolved before the first version
+    assert_not_found(
+        client
+            .get_dyn_path_at_unchecked(None, &path, &instants[0])
+            .await,
+    );
+
+    // resolve the path between the versions, exactly at them, and after them
+    for (instant, version) in [
+        (&instants[1], &versions[0]),
+        (&instants[2], &versions[1]),
+        (&versions[1].metadata.created_date, &versions[1]),
+        (&instants[3], &versions[2]),
+    ] {
+        let dyn_path_at = client
+            .get_dyn_path_at_unchecked(None, &path, instant)
+            .await
+            .unwrap();
+        assert_eq!(&dyn_path_at.data, &version.data);
+    }
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(None, &path.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_delete_owned() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register a guarantee
+    let guarantee = common::create_guarantee(&client, "dyn-path-delete").await;
+    let guarantee_account = guarantee.account_ref();
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str("ipdis-api-postgres-test-delete"),
+        kind: Hash::with_str("ipdis-api-postgres-test-delete"),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "bafkreihm64a7oj6z4llxysvetldpxpgjs4tyvsqbbpo65olbyegpktkdli"
+                .parse()
+                .unwrap(),
+            len: 13,
+        },
+    };
+
+    // cleanup test data
+    client
+        .delete_dyn_path_all_unchecked(None, &dyn_path.namespace)
+        .await
+        .unwrap();
+
+    // put the path by both of the server and the guarantee
+    for ipiis in [ipiis, &guarantee] {
+        let dyn_path = ipiis.sign_owned(*account, dyn_path).unwrap();
+        client.put_dyn_path_unchecked(&dyn_path).await.unwrap();
+    }
+
+    // delete the paths of the guarantee by its request
+    let query = guarantee
+        .sign_owned(
+            *account,
+            DeleteDynPaths {
+                namespace: dyn_path.namespace,
+            },
+        )
+        .unwrap();
+    client.delete_dyn_path_all(&query).await.unwrap();
+
+    // ensure that only the paths of the guarantee have been deleted
+    assert_not_found(
+        client
+            .get_dyn_path_unchecked(Some(guarantee_account), &dyn_path.remove_path())
+            .await,
+    );
+    client
+        .get_dyn_path_unchecked(None, &dyn_path.remove_path())
+        .await
+        .unwrap();
+
+    // delete the paths of the server
+    client
+        .delete_dyn_path_all_unchecked(Some(account), &dyn_path.namespace)
+        .await
+        .unwrap();
+    assert_not_found(
+        client
+            .get_dyn_path_unchecked(None, &dyn_path.remove_path())
+            .await,
+    );
+}
@@ -0,0 +1,156 @@
+mod common;
+
+use ipdis_api::{
+    common::{GetWords, GetWordsParent, Ipdis, IpdisError},
+    storage::IpdisStorage,
+};
+use ipdis_api_common::{policy::RequestPolicy, quota::QuotaPolicy};
+use ipiis_api::{common::Ipiis, server::IpiisServer};
+use ipis::{
+    core::{
+        anyhow::{anyhow, Error, Result},
+        value::hash::Hash,
+    },
+    path::{DynPath, Path},
+    tokio,
+};
+
+fn assert_error<T>(result: Result<T>, kind: &str) {
+    match result {
+        Ok(_) => panic!("expected {kind}, but succeeded"),
+        Err(e) => assert_eq!(IpdisError::from(e).kind(), kind),
+    }
+}
+
+#[test]
+fn test_from_remote() {
+    // the local errors are kept as they are
+    let error: Error = IpdisError::NotFound("no such word".into()).into();
+    assert_eq!(
+        IpdisError::from(&error),
+        IpdisError::NotFound("no such word".into()),
+    );
+
+    // the remote errors are restored from their archived forms
+    for error in [
+        IpdisError::Unauthenticated("failed to authenticate the guarantee".into()),
+        IpdisError::Conflict("duplicated word\nipdis-error:00".into()),
+    ] {
+        let remote = anyhow!("{}", error.clone().into_remote());
+        assert_eq!(IpdisError::from(remote), error);
+    }
+
+    // ensure that the messages are not parsed
+    for message in [
+        "Conflict: duplicated word",
+        "failed to put: Conflict: duplicated word",
+        "unknown\nipdis-error:00",
+        "unknown",
+    ] {
+        assert_eq!(
+            IpdisError::from(anyhow!(message)),
+            IpdisError::Backend(message.into()),
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_remote() {
+    // deploy a server
+    let (server, client) = common::deploy_server("error", 9821).await;
+    let ipiis: &IpiisServer = (*server).as_ref();
+    let server_account = *ipiis.account_ref();
+    let namespace = "ipdis-api-postgres-test-error";
+
+    // create a dynamic path
+    let dyn_path = DynPath {
+        namespace: Hash::with_str(namespace),
+        kind: Hash::with_str(namespace),
+        word: Hash::with_str("my model"),
+        path: Path {
+            value: "bafybeie52ly6uafpr4h3ih24mqa4twtojppo6366kyi74ejtd4sxv2fezm"
+                .parse()
+                .unwrap(),
+            len: 496_300_196,
+        },
+    };
+
+    // NotFound: the path is not put yet
+    assert_error(
+        client
+            .get_dyn_path_unchecked(None, &dyn_path.remove_path())
+            .await,
+        "NotFound",
+    );
+
+    // MalformedQuery: the range of the words is empty
+    let query = GetWords {
+        word: common::create_word(namespace, "hello world").key,
+        parent: GetWordsParent::None,
+        cursor: None,
+        start_index: 1,
+        end_index: 0,
+    };
+    assert_error(
+        client.get_word_many_unchecked(None, &query).await,
+        "MalformedQuery",
+    );
+
+    // Conflict: the signed path is put again
+    let path = client.sign_owned(server_account, dyn_path).unwrap();
+    client.put_dyn_path_unchecked(&path).await.unwrap();
+    assert_error(client.put_dyn_path_unchecked(&path).await, "Conflict");
+
+    // Backend: the stored path has been altered
+    let dyn_path_altered = DynPath {
+        word: Hash::with_str("my altered model"),
+        ..dyn_path
+    };
+    let mut altered = ipiis
+        .sign_as_guarantor(client.sign_owned(server_account, dyn_path_altered).unwrap())
+        .unwrap();
+    altered.data.path.len += 1;
+    server
+        .storage()
+        .put_dyn_path(&altered, &QuotaPolicy::default(), &RequestPolicy::default())
+        .await
+        .unwrap();
+    assert_error(
+        client
+            .get_dyn_path_unchecked(None, &dyn_path_altered.remove_path())
+            .await,
+        "Backend",
+    );
+
+    // Unauthenticated: the account is not registered
+    let stranger = common::connect_client("error-stranger", &server, 9821).await;
+    assert_error(
+        stranger
+            .get_dyn_path_unchecked(None, &dyn_path.remove_path())
+            .await,
+        "Unauthenticated",
+    );
+
+    // Unavailable: the server is shutting down
+    server.drain().close();
+    assert_error(client.get_health().await, "Unavailable");
+
+    // deploy a server, which limits the requests
+    ::std::env::set_var("ipdis_policy_max_lifetime_secs", "1");
+    ::std::env::set_var("ipdis_quota_rate_per_sec", "1");
+    let (_server, client) = common::deploy_server("error-limited", 9822).await;
+    ::std::env::remove_var("ipdis_policy_max_lifetime_secs");
+    ::std::env::remove_var("ipdis_quota_rate_per_sec");
+
+    // Expired: the request lives longer than a second
+    assert_error(client.get_health().await, "Expired");
+
+    // Exhausted: the rate limit is exceeded
+    assert_error(client.get_health().await, "Exhausted");
+
+    // cleanup test data
+    server
+        .delete_dyn_path_all_unchecked(None, &dyn_path.namespace)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::{GetGuarantees, GuaranteeRole, Ipdis, IpdisError},
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{core::anyhow::Result, env::Infer, tokio};
+
+fn assert_rejected<T>(result: Result<T>) {
+    assert!(matches!(
+        result.map_err(IpdisError::from),
+        Err(IpdisError::Unauthenticated(_)),
+    ))
+}
+
+#[tokio::test]
+async fn test_revoke() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register the guarantees
+    let guarantee = common::create_guarantee(&client, "revoke").await;
+    let guarantee_account = guarantee.account_ref();
+    let other = common::create_guarantee(&client, "revoke-other").await;
+
+    client
+        .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+        .await
+        .unwrap();
+
+    // ensure that the other guarantees cannot revoke the guarantee
+    let revocation = other.sign_owned(*account, *guarantee_account).unwrap();
+    assert_rejected(client.revoke_guarantee(&revocation).await);
+    client
+        .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+        .await
+        .unwrap();
+
+    // the guarantee can revoke itself
+    let revocation = guarantee.sign_owned(*account, *guarantee_account).unwrap();
+    client.revoke_guarantee(&revocation).await.unwrap();
+
+    // ensure that the revoked guarantee is rejected
+    assert_rejected(
+        client
+            .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+            .await,
+    );
+    assert!(matches!(
+        client
+            .get_guarantee_unchecked(guarantee_account)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::NotFound(_)),
+    ));
+
+    // the guarantee added again after the revocation is accepted
+    common::add_guarantee(&client, &guarantee).await;
+    client
+        .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+        .await
+        .unwrap();
+
+    // the guarantor can revoke the guarantee
+    let revocation = ipiis.sign_owned(*account, *guarantee_account).unwrap();
+    client.revoke_guarantee(&revocation).await.unwrap();
+    assert_rejected(
+        client
+            .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+            .await,
+    );
+
+    // the revocation put after the guarantee is applied, even if signed before it
+    let revocation = ipiis.sign_owned(*account, *guarantee_account).unwrap();
+    common::add_guarantee(&client, &guarantee).await;
+    client
+        .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+        .await
+        .unwrap();
+    client.revoke_guarantee(&revocation).await.unwrap();
+    assert_rejected(
+        client
+            .ensure_registered(guarantee_account, account, GuaranteeRole::ReadOnly, None)
+            .await,
+    );
+
+    // the revocations do not affect the other guarantees
+    client
+        .ensure_registered(other.account_ref(), account, GuaranteeRole::ReadOnly, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_get_many() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register a guarantee 3 times
+    let guarantee = common::create_guarantee(&client, "get-many").await;
+    let guarantee_account = guarantee.account_ref();
+    for _ in 1..3 {
+        common::add_guarantee(&client, &guarantee).await;
+    }
+
+    // iterate the guarantees by pages
+    let mut records = vec![];
+    for (start_index, end_index, len) in [(0, 2, 2), (2, 4, 1), (4, 6, 0)] {
+        let query = GetGuarantees {
+            guarantee: Some(*guarantee_account),
+            start_index,
+            end_index,
+        };
+        let page = client.get_guarantee_many_unchecked(&query).await.unwrap();
+        assert_eq!(page.len(), len);
+        records.extend(page);
+    }
+
+    // ensure that the pages are not overlapped, the latest first
+    assert!(records
+        .iter()
+        .all(|record| &record.data == guarantee_account));
+    assert!(records
+        .windows(2)
+        .all(|pair| pair[0].metadata.created_date > pair[1].metadata.created_date));
+
+    // ensure that the malformed ranges are rejected
+    let query = GetGuarantees {
+        guarantee: Some(*guarantee_account),
+        start_index: 2,
+        end_index: 2,
+    };
+    assert!(matches!(
+        client
+            .get_guarantee_many_unchecked(&query)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::MalformedQuery(_)),
+    ));
+
+    // the guarantee can list its own records
+    let query = GetGuarantees {
+        guarantee: Some(*guarantee_account),
+        start_index: 0,
+        end_index: 10,
+    };
+    let query = guarantee.sign_owned(*account, query).unwrap();
+    assert_eq!(client.get_guarantee_many(&query).await.unwrap().len(), 3);
+
+    // ensure that the guarantee cannot list the others
+    let query = GetGuarantees {
+        guarantee: None,
+        start_index: 0,
+        end_index: 10,
+    };
+    let query = guarantee.sign_owned(*account, query).unwrap();
+    assert_rejected(client.get_guarantee_many(&query).await);
+}
@@ -0,0 +1,63 @@
+use std::net::{Ipv4Addr, SocketAddr};
+
+use ipdis_api::{
+    client::IpdisClient,
+    storage::{IpdisStorage, Storage},
+};
+use ipdis_common::{Ipdis, KIND};
+use ipiis_api::{client::IpiisClient, common::Ipiis, server::IpiisServer};
+use ipis::{env::Infer, tokio};
+
+mod common;
+
+#[tokio::test]
+async fn test_health() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // ensure that the storage is ready
+    let health = client.get_health().await.unwrap();
+    assert_eq!(&health.account, account);
+    assert_eq!(health.storage_error, None);
+    assert_eq!(health.pending_migrations, 0);
+    assert!(health.is_ready());
+}
+
+#[tokio::test]
+async fn test_health_remote() {
+    // deploy a server
+    let (server, client) = common::deploy_server("health-remote", 9811).await;
+    let server: &IpiisServer = (*server).as_ref();
+
+    // ensure that the server signs its health
+    let health = client.get_health().await.unwrap();
+    assert_eq!(&health.account, server.account_ref());
+    assert!(health.is_ready());
+
+    // ensure that the health of another account is rejected
+    client
+        .set_account_primary(KIND.as_ref(), client.account_ref())
+        .await
+        .unwrap();
+    client
+        .set_address(
+            KIND.as_ref(),
+            client.account_ref(),
+            &SocketAddr::from((Ipv4Addr::LOCALHOST, 9811)),
+        )
+        .await
+        .unwrap();
+    assert!(client.get_health().await.is_err());
+}
+
+#[tokio::test]
+async fn test_storage_verify_only() {
+    // create a client, which migrates the storage
+    let _client = IpdisClient::infer().await;
+
+    // ensure that the storage opened without migrating is ready
+    let storage = Storage::try_infer_verify_only().await.unwrap();
+    assert_eq!(storage.get_pending_migrations().await.unwrap(), 0);
+}
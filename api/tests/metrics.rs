@@ -0,0 +1,79 @@
+mod common;
+
+use ipdis_api::{client::IpdisClient, common::Ipdis};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::value::hash::Hash,
+    env::Infer,
+    tokio::{
+        self,
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpStream,
+    },
+};
+
+#[tokio::test]
+async fn test_auth_failures() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // sign as guarantee, and then tamper the data
+    let mut record = ipiis.sign_owned(*account, Hash::with_str("")).unwrap();
+    record.data = Hash::with_str("tampered");
+
+    // ensure that the tampered record is rejected and counted
+    assert!(client.ensure_verified(&record).is_err());
+    assert!(client
+        .metrics()
+        .render(None)
+        .contains("ipdis_auth_failures_total 1\n"));
+
+    // ensure that the calls are measured
+    client.metrics().start("WordPut").succeed();
+    drop(client.metrics().start("WordPut"));
+
+    let metrics = client.metrics().render(None);
+    assert!(metrics.contains("ipdis_calls_total{call=\"WordPut\",result=\"ok\"} 1\n"));
+    assert!(metrics.contains("ipdis_calls_total{call=\"WordPut\",result=\"error\"} 1\n"));
+    assert!(metrics.contains("ipdis_call_duration_seconds_count{call=\"WordPut\"} 2\n"));
+}
+
+#[tokio::test]
+async fn test_scrape() {
+    // deploy a server along with the metrics listener
+    let address = "127.0.0.1:9824";
+    ::std::env::set_var("ipdis_metrics_address", address);
+    let (_server, client) = common::deploy_server("metrics", 9823).await;
+
+    // make a call to be measured
+    client.get_health().await.unwrap();
+
+    // scrape the metrics, waiting for the listener to be bound
+    let mut stream = None;
+    for _ in 0..50 {
+        match TcpStream::connect(address).await {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(_) => tokio::time::sleep(::std::time::Duration::from_millis(100)).await,
+        }
+    }
+    let mut stream = stream.expect("the metrics listener should be bound");
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+        .await
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    // ensure that the call is counted and measured
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains("ipdis_calls_total{call=\"HealthGet\",result=\"ok\"} 1\n"));
+    assert!(
+        response.contains("ipdis_call_duration_seconds_bucket{call=\"HealthGet\",le=\"+Inf\"} 1\n")
+    );
+    assert!(response.contains("ipdis_metrics_connection_errors_total 0\n"));
+}
@@ -0,0 +1,103 @@
+use ipdis_api::{client::IpdisClient, common::IpdisError};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{
+        chrono::{Duration, Utc},
+        value::chrono::NaiveDateTime,
+    },
+    env::Infer,
+    tokio,
+};
+
+#[tokio::test]
+async fn test_replay() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // sign as guarantee
+    let request = ipiis.sign_owned(*account, *account).unwrap();
+
+    // the first request is accepted
+    client.ensure_fresh(&request.metadata).await.unwrap();
+
+    // ensure that the replayed request is rejected
+    assert!(matches!(
+        client
+            .ensure_fresh(&request.metadata)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Conflict(_)),
+    ));
+
+    // the new request is accepted again
+    let request = ipiis.sign_owned(*account, *account).unwrap();
+    client.ensure_fresh(&request.metadata).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_future() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // sign as guarantee, as if it is created in the future
+    let mut request = ipiis.sign_owned(*account, *account).unwrap();
+    request.metadata.data.created_date =
+        NaiveDateTime(Utc::now().naive_utc() + Duration::hours(1)).to_utc();
+
+    // ensure that the request is rejected
+    assert!(matches!(
+        client
+            .ensure_fresh(&request.metadata)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Expired(_)),
+    ));
+}
+
+#[tokio::test]
+async fn test_lifetime() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // sign as guarantee, as if it never expires
+    let mut request = ipiis.sign_owned(*account, *account).unwrap();
+    request.metadata.data.expiration_date = None;
+
+    // ensure that its nonce would not be kept forever
+    let policy = client.policy();
+    assert_eq!(
+        policy.retention_date(&request.metadata),
+        request.metadata.data.created_date.naive_utc() + policy.max_lifetime + policy.clock_skew,
+    );
+
+    // ensure that the request is rejected
+    assert!(matches!(
+        client
+            .ensure_fresh(&request.metadata)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Expired(_)),
+    ));
+
+    // sign as guarantee, as if it lives longer than the policy
+    let mut request = ipiis.sign_owned(*account, *account).unwrap();
+    request.metadata.data.expiration_date = Some(
+        NaiveDateTime(Utc::now().naive_utc() + client.policy().max_lifetime + Duration::hours(1))
+            .to_utc(),
+    );
+
+    // ensure that the request is rejected
+    assert!(matches!(
+        client
+            .ensure_fresh(&request.metadata)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Expired(_)),
+    ));
+}
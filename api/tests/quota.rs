@@ -0,0 +1,147 @@
+mod common;
+
+use std::sync::Arc;
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::{Ipdis, IpdisError},
+};
+use ipdis_api_common::quota::{QuotaPolicy, RateLimit};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{core::value::hash::Hash, env::Infer, tokio};
+
+#[tokio::test]
+async fn test_rate() {
+    // create a client with a rate limit
+    let client = IpdisClient::infer().await.with_quota(QuotaPolicy {
+        rate: Some(RateLimit {
+            per_sec: 1,
+            burst: 2,
+        }),
+        ..Default::default()
+    });
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // the burst is accepted
+    client.ensure_rate(account, "WordPut").unwrap();
+    client.ensure_rate(account, "WordPut").unwrap();
+
+    // ensure that the exceeding request is rejected
+    assert!(matches!(
+        client
+            .ensure_rate(account, "WordPut")
+            .map_err(IpdisError::from),
+        Err(IpdisError::Exhausted(_)),
+    ));
+
+    // ensure that the other IO calls have their own buckets
+    client.ensure_rate(account, "WordGetMany").unwrap();
+
+    let quota = client.get_quota_unchecked(account).await.unwrap();
+    assert_eq!(quota.rate_per_sec, Some(1));
+    assert_eq!(quota.rate_burst, Some(2));
+    assert_eq!(quota.rate_remaining, Some(0));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_quota() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-quota", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // limit the words to the ones stored by now, and one more
+    let usage = client.get_quota_unchecked(account).await.unwrap();
+    let client = Arc::new(client.with_quota(QuotaPolicy {
+        max_words: Some(usage.words + 1),
+        ..Default::default()
+    }));
+    let ipiis: &IpiisClient = (*client).as_ref();
+    let account = ipiis.account_ref();
+
+    // put a word in IPDIS
+    let signed = ipiis.sign_owned(*account, word).unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    let quota = client.get_quota_unchecked(account).await.unwrap();
+    assert_eq!(quota.max_words, Some(usage.words + 1));
+    assert_eq!(quota.words, usage.words + 1);
+
+    // ensure that the exceeding word is rejected
+    let signed = ipiis.sign_owned(*account, word).unwrap();
+    assert!(matches!(
+        client
+            .put_word_unchecked(&parent, &signed)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Exhausted(_)),
+    ));
+
+    // ensure that the rejected word can be put again, as its nonce is not recorded
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+    client.put_word_unchecked(&parent, &signed).await.unwrap();
+
+    // ensure that the concurrent words cannot exceed the quota together
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+    let tasks: Vec<_> = (0..8)
+        .map(|_| {
+            let client = client.clone();
+
+            // sign as guarantee
+            let word = ipiis.sign_owned(*account, word).unwrap();
+
+            tokio::spawn(async move { client.put_word_unchecked(&parent, &word).await })
+        })
+        .collect();
+    let mut accepted = 0;
+    for task in tasks {
+        if task.await.unwrap().is_ok() {
+            accepted += 1;
+        }
+    }
+    assert_eq!(accepted, 1);
+
+    let quota = client.get_quota_unchecked(account).await.unwrap();
+    assert_eq!(quota.words, usage.words + 1);
+
+    // ensure that only the words over the quota are rejected in a batch
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+    let words: Vec<_> = (0..3)
+        .map(|_| (parent, ipiis.sign_owned(*account, word).unwrap()))
+        .collect();
+    let errors = client.put_word_many_unchecked(None, &words).await.unwrap();
+    assert_eq!(errors.len(), 2);
+    for (error, index) in errors.iter().zip(1..) {
+        assert_eq!(error.index, index);
+        assert!(matches!(error.error, IpdisError::Exhausted(_)));
+    }
+
+    let quota = client.get_quota_unchecked(account).await.unwrap();
+    assert_eq!(quota.words, usage.words + 1);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,263 @@
+mod common;
+
+use ipdis_api::{
+    client::IpdisClient,
+    common::{GuaranteeRole, GuaranteeScope, Ipdis, IpdisError},
+    storage::IpdisStorage,
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{anyhow::Result, value::hash::Hash},
+    env::Infer,
+    tokio,
+};
+
+fn assert_rejected(result: Result<()>) {
+    assert!(matches!(
+        result.map_err(IpdisError::from),
+        Err(IpdisError::Unauthenticated(_)),
+    ))
+}
+
+#[tokio::test]
+async fn test_scope() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample scope
+    let namespace = Hash::with_str("ipdis-api-postgres-test-scope");
+    let scope = GuaranteeScope {
+        account: *account,
+        role: GuaranteeRole::ReadOnly,
+        namespaces: Some(vec![namespace]),
+    };
+
+    // the reads in the namespace are allowed
+    scope
+        .ensure_allowed(GuaranteeRole::ReadOnly, Some(&namespace))
+        .unwrap();
+    scope.ensure_allowed(GuaranteeRole::ReadOnly, None).unwrap();
+
+    // ensure that the writes and the other namespaces are rejected
+    assert_rejected(scope.ensure_allowed(GuaranteeRole::ReadWrite, Some(&namespace)));
+    assert_rejected(scope.ensure_allowed(GuaranteeRole::Admin, None));
+    assert_rejected(scope.ensure_allowed(
+        GuaranteeRole::ReadOnly,
+        Some(&Hash::with_str("ipdis-api-postgres-test-scope-other")),
+    ));
+
+    // ensure that the wider scopes cannot be given
+    scope.ensure_covers(&scope).unwrap();
+    assert_rejected(scope.ensure_covers(&GuaranteeScope {
+        role: GuaranteeRole::ReadWrite,
+        ..scope.clone()
+    }));
+    assert_rejected(scope.ensure_covers(&GuaranteeScope {
+        namespaces: None,
+        ..scope.clone()
+    }));
+
+    // store the scope
+    let scope = ipiis.sign_owned(*account, scope).unwrap();
+    client.set_guarantee_scope_unchecked(&scope).await.unwrap();
+
+    // ensure that the latest scope is read back
+    let scope_from_storage = client
+        .storage()
+        .get_guarantee_scope_latest(account, account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(scope_from_storage.data, scope.data);
+
+    // the server itself is not limited by its scope
+    client
+        .ensure_registered(account, account, GuaranteeRole::Admin, None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_scope_registered() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register a guarantee
+    let guarantee = common::create_guarantee(&client, "scope").await;
+    let guarantee_account = guarantee.account_ref();
+
+    let namespace = Hash::with_str("ipdis-api-postgres-test-scope-registered");
+    let namespace_other = Hash::with_str("ipdis-api-postgres-test-scope-registered-other");
+
+    // the guarantees without any scope can read and write, but not administrate
+    client
+        .ensure_registered(
+            guarantee_account,
+            account,
+            GuaranteeRole::ReadWrite,
+            Some(&namespace),
+        )
+        .await
+        .unwrap();
+    assert_rejected(
+        client
+            .ensure_registered(guarantee_account, account, GuaranteeRole::Admin, None)
+            .await,
+    );
+
+    // give the guarantee a read-only scope in the namespace
+    let scope = ipiis
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::ReadOnly,
+                namespaces: Some(vec![namespace]),
+            },
+        )
+        .unwrap();
+    client.set_guarantee_scope(&scope).await.unwrap();
+
+    // the reads in the namespace are allowed
+    client
+        .ensure_registered(
+            guarantee_account,
+            account,
+            GuaranteeRole::ReadOnly,
+            Some(&namespace),
+        )
+        .await
+        .unwrap();
+
+    // ensure that the writes and the other namespaces are rejected
+    assert_rejected(
+        client
+            .ensure_registered(
+                guarantee_account,
+                account,
+                GuaranteeRole::ReadWrite,
+                Some(&namespace),
+            )
+            .await,
+    );
+    assert_rejected(
+        client
+            .ensure_registered(
+                guarantee_account,
+                account,
+                GuaranteeRole::ReadOnly,
+                Some(&namespace_other),
+            )
+            .await,
+    );
+
+    // make the guarantee an admin in the namespace
+    let scope = ipiis
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::Admin,
+                namespaces: Some(vec![namespace]),
+            },
+        )
+        .unwrap();
+    client.set_guarantee_scope(&scope).await.unwrap();
+
+    // ensure that the admin cannot widen its own scope
+    let scope = guarantee
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::Admin,
+                namespaces: None,
+            },
+        )
+        .unwrap();
+    assert_rejected(client.set_guarantee_scope(&scope).await);
+
+    // the narrower scope can be given
+    let scope = guarantee
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::ReadOnly,
+                namespaces: Some(vec![namespace]),
+            },
+        )
+        .unwrap();
+    client.set_guarantee_scope(&scope).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_scope_latest() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register a guarantee
+    let guarantee = common::create_guarantee(&client, "scope-latest").await;
+    let guarantee_account = guarantee.account_ref();
+
+    let namespaces = vec![
+        Hash::with_str("ipdis-api-postgres-test-scope-latest-b"),
+        Hash::with_str("ipdis-api-postgres-test-scope-latest-a"),
+    ];
+
+    // sign the scopes, and put them in the reverse order
+    let scope_signed_first = ipiis
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::ReadOnly,
+                namespaces: Some(namespaces),
+            },
+        )
+        .unwrap();
+    let scope_signed_last = ipiis
+        .sign_owned(
+            *account,
+            GuaranteeScope {
+                account: *guarantee_account,
+                role: GuaranteeRole::Admin,
+                namespaces: None,
+            },
+        )
+        .unwrap();
+    let storage = client.storage();
+    storage
+        .put_guarantee_scope(&scope_signed_last)
+        .await
+        .unwrap();
+    storage
+        .put_guarantee_scope(&scope_signed_first)
+        .await
+        .unwrap();
+
+    // ensure that the last one put wins, with its namespaces in order
+    let scope_from_storage = storage
+        .get_guarantee_scope_latest(guarantee_account, account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(scope_from_storage.data, scope_signed_first.data);
+
+    // ensure that the scopes are deleted along with the guarantee
+    storage
+        .delete_guarantee_all(guarantee_account)
+        .await
+        .unwrap();
+    assert!(storage
+        .get_guarantee_scope_latest(guarantee_account, account)
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -1,14 +1,17 @@
+mod common;
+
+use std::sync::Arc;
+
 use ipdis_api::{
     client::IpdisClient,
-    common::{GetWords, GetWordsCounts, GetWordsParent, Ipdis},
+    common::{Cursor, DeleteWords, GetWords, GetWordsCounts, GetWordsParent, Ipdis, IpdisError},
 };
+use ipdis_api_common::policy::RequestPolicy;
 use ipiis_api::{client::IpiisClient, common::Ipiis};
 use ipis::{
-    core::value::{hash::Hash, text::Text},
+    core::{chrono::Duration, value::hash::Hash},
     env::Infer,
-    path::Path,
     tokio,
-    word::{Word, WordHash, WordKey},
 };
 
 #[tokio::test]
@@ -19,27 +22,8 @@ async fn test_create() {
     let account = ipiis.account_ref();
 
     // create a sample word to be stored
-    let namespace = "ipdis-api-postgres-test";
-    let kind = "ipdis-api-postgres-test";
-    let parent = "";
-    let word = Word {
-        key: WordKey {
-            namespace: namespace.to_string(),
-            text: Text::with_en_us("hello world"),
-        },
-        kind: kind.to_string(),
-        relpath: true,
-        path: Path {
-            value: "bafkreihm64a7oj6z4llxysvetldpxpgjs4tyvsqbbpo65olbyegpktkdli"
-                .parse()
-                .unwrap(),
-            len: 13,
-        },
-    };
-
-    // make it hash
-    let word: WordHash = word.into();
-    let parent = Hash::with_str(parent);
+    let word = common::create_word("ipdis-api-postgres-test", "hello world");
+    let parent = Hash::with_str("");
     let parent_word = {
         let mut word = word;
         word.key.text.msg = parent;
@@ -48,7 +32,7 @@ async fn test_create() {
 
     // cleanup test data
     client
-        .delete_word_all_unchecked(&word.key.namespace)
+        .delete_word_all_unchecked(None, &word.key.namespace)
         .await
         .unwrap();
 
@@ -77,13 +61,14 @@ async fn test_create() {
             &GetWords {
                 word: parent_word.key,
                 parent: GetWordsParent::Duplicated,
+                cursor: None,
                 start_index: 0,
                 end_index: 1,
             },
         )
         .await
         .unwrap();
-    assert_eq!(&words_from_ipdis[0].data, &word);
+    assert_eq!(&words_from_ipdis.items[0].data, &word);
 
     // get the word counts
     let count_from_ipdis = client
@@ -108,12 +93,14 @@ async fn test_create() {
                     word: parent_word.key,
                     parent: true,
                     owned: false,
+                    cursor: None,
                     start_index: 0,
                     end_index: 1,
                 }
             )
             .await
             .unwrap()
+            .items
             .pop()
             .unwrap()
             .count,
@@ -129,12 +116,14 @@ async fn test_create() {
                     word: parent_word.key,
                     parent: true,
                     owned: true,
+                    cursor: None,
                     start_index: 0,
                     end_index: 1,
                 }
             )
             .await
             .unwrap()
+            .items
             .pop()
             .unwrap()
             .count,
@@ -143,7 +132,7 @@ async fn test_create() {
 
     // cleanup test data
     client
-        .delete_word_all_unchecked(&word.key.namespace)
+        .delete_word_all_unchecked(None, &word.key.namespace)
         .await
         .unwrap();
 
@@ -156,3 +145,506 @@ async fn test_create() {
         0,
     );
 }
+
+#[tokio::test]
+async fn test_paginate() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-paginate", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS (* 3 times)
+    let count = 3u32;
+    for _ in 0..count {
+        // sign as guarantee
+        let word = ipiis.sign_owned(*account, word).unwrap();
+
+        // put the word in IPDIS
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // iterate the words with the cursor
+    let mut words_from_ipdis = vec![];
+    let mut cursor = None;
+    loop {
+        let page = client
+            .get_word_many_unchecked(
+                None,
+                &GetWords {
+                    word: word.key,
+                    parent: GetWordsParent::None,
+                    cursor,
+                    start_index: 0,
+                    end_index: 2,
+                },
+            )
+            .await
+            .unwrap();
+        words_from_ipdis.extend(page.items);
+
+        cursor = page.cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(words_from_ipdis.len(), count as usize);
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_forged_cursor() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-forged-cursor", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word in IPDIS (* 3 times), to be paginated
+    for _ in 0..3 {
+        let word = ipiis.sign_owned(*account, word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // ensure that the forged cursors are rejected
+    let query = GetWords {
+        word: word.key,
+        parent: GetWordsParent::None,
+        cursor: None,
+        start_index: 0,
+        end_index: 2,
+    };
+    let mut tampered = client
+        .get_word_many_unchecked(None, &query)
+        .await
+        .unwrap()
+        .cursor
+        .unwrap();
+    tampered.data = Cursor::with_id(u64::MAX);
+    let guarantee = common::create_guarantee(&client, "word-cursor").await;
+    for (cursor, kind) in [
+        (tampered, "Unauthenticated"),
+        (
+            guarantee
+                .sign_owned(*account, Cursor::with_id(u64::MAX))
+                .unwrap(),
+            "MalformedQuery",
+        ),
+    ] {
+        let query = GetWords {
+            cursor: Some(cursor),
+            ..query
+        };
+        let error = client
+            .get_word_many_unchecked(None, &query)
+            .await
+            .map_err(IpdisError::from)
+            .unwrap_err();
+        assert_eq!(error.kind(), kind);
+    }
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_put_many() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create sample words to be stored
+    let parent = Hash::with_str("");
+    let words: Vec<_> = ["hello", "world", "hello"]
+        .into_iter()
+        .map(|text| common::create_word("ipdis-api-postgres-test-many", text))
+        .collect();
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &words[0].key.namespace)
+        .await
+        .unwrap();
+
+    // sign as guarantee
+    let words: Vec<_> = words
+        .into_iter()
+        .map(|word| (parent, ipiis.sign_owned(*account, word).unwrap()))
+        .collect();
+
+    // put the words in IPDIS at once
+    let errors = client.put_word_many_unchecked(None, &words).await.unwrap();
+    assert!(errors.is_empty());
+
+    // get the word counts (the counts are aggregated)
+    for (word, count) in [(&words[0].1.data, 2), (&words[1].1.data, 1)] {
+        assert_eq!(
+            client
+                .get_word_count_unchecked(None, &word.key, false)
+                .await
+                .unwrap(),
+            count,
+        );
+    }
+
+    // create a batch mixed with the rejected words
+    let guarantee = common::create_guarantee(&client, "word-many").await;
+    let word = words[1].1.data;
+    let mut tampered = ipiis.sign_owned(*account, word).unwrap();
+    tampered.data.key.text.msg = Hash::with_str("tampered");
+    let mixed = vec![
+        (parent, ipiis.sign_owned(*account, word).unwrap()),
+        // bad signature
+        (parent, tampered),
+        // wrong guarantee
+        (parent, guarantee.sign_owned(*account, word).unwrap()),
+        // replayed word
+        words[1],
+        (parent, ipiis.sign_owned(*account, word).unwrap()),
+    ];
+
+    // ensure that only the rejected words are reported
+    let errors = client
+        .put_word_many_unchecked(Some(account), &mixed)
+        .await
+        .unwrap();
+    assert_eq!(
+        errors.iter().map(|error| error.index).collect::<Vec<_>>(),
+        [1, 2, 3],
+    );
+
+    // ensure that the valid words have been stored
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        3,
+    );
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &words[0].1.data.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn test_put_concurrent() {
+    // create a client
+    let client = Arc::new(IpdisClient::infer().await);
+    let ipiis: &IpiisClient = (*client).as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-concurrent", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the same word from many tasks at once
+    let count = 64u32;
+    let tasks: Vec<_> = (0..count)
+        .map(|_| {
+            let client = client.clone();
+
+            // sign as guarantee
+            let word = ipiis.sign_owned(*account, word).unwrap();
+
+            tokio::spawn(async move { client.put_word_unchecked(&parent, &word).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+
+    // ensure that no increments have been lost
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        count,
+    );
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, true)
+            .await
+            .unwrap(),
+        count,
+    );
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_namespace_stats() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create the same words in the different namespaces
+    let parent = Hash::with_str("");
+    let words: Vec<_> = [
+        ("ipdis-api-postgres-test-stats-a", "hello"),
+        ("ipdis-api-postgres-test-stats-a", "world"),
+        ("ipdis-api-postgres-test-stats-a", "hello"),
+        ("ipdis-api-postgres-test-stats-b", "hello"),
+    ]
+    .into_iter()
+    .map(|(namespace, text)| common::create_word(namespace, text))
+    .collect();
+    let (word_a, word_b) = (words[0], words[3]);
+
+    // cleanup test data
+    for word in [&word_a, &word_b] {
+        client
+            .delete_word_all_unchecked(None, &word.key.namespace)
+            .await
+            .unwrap();
+    }
+
+    // put the words in IPDIS
+    for word in words {
+        let word = ipiis.sign_owned(*account, word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+
+    // ensure that the same words in the different namespaces are not mixed
+    for (word, count) in [(&word_a, 2), (&word_b, 1)] {
+        for owned in [false, true] {
+            assert_eq!(
+                client
+                    .get_word_count_unchecked(None, &word.key, owned)
+                    .await
+                    .unwrap(),
+                count,
+            );
+        }
+    }
+
+    // get the stats of the namespace
+    let stats = client
+        .get_namespace_stats_unchecked(&word_a.key.namespace)
+        .await
+        .unwrap();
+    assert_eq!(stats.total_words, 3);
+    assert_eq!(stats.distinct_words, 2);
+    assert_eq!(stats.distinct_guarantees, 1);
+
+    // cleanup test data
+    for word in [&word_a, &word_b] {
+        client
+            .delete_word_all_unchecked(None, &word.key.namespace)
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_put_tampered() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-tampered", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // sign as guarantee, and then tamper the word
+    let mut word = ipiis.sign_owned(*account, word).unwrap();
+    word.data.path.len += 1;
+
+    // ensure that the tampered word is rejected
+    assert!(client.put_word_unchecked(&parent, &word).await.is_err());
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.data.key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+}
+
+#[tokio::test]
+async fn test_delete_owned() {
+    // create a client
+    let client = IpdisClient::infer().await;
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // register a guarantee
+    let guarantee = common::create_guarantee(&client, "word-delete").await;
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-delete", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // put the word by both of the server and the guarantee
+    for ipiis in [ipiis, &guarantee] {
+        let word = ipiis.sign_owned(*account, word).unwrap();
+        client.put_word_unchecked(&parent, &word).await.unwrap();
+    }
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        2,
+    );
+
+    // delete the words of the guarantee by its request
+    let query = guarantee
+        .sign_owned(
+            *account,
+            DeleteWords {
+                namespace: word.key.namespace,
+            },
+        )
+        .unwrap();
+    client.delete_word_all(&query).await.unwrap();
+
+    // ensure that only the words of the guarantee have been deleted
+    for owned in [false, true] {
+        assert_eq!(
+            client
+                .get_word_count_unchecked(None, &word.key, owned)
+                .await
+                .unwrap(),
+            1,
+        );
+    }
+
+    // delete the words of the server
+    client
+        .delete_word_all_unchecked(Some(account), &word.key.namespace)
+        .await
+        .unwrap();
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.key, false)
+            .await
+            .unwrap(),
+        0,
+    );
+}
+
+#[tokio::test]
+async fn test_put_long_lived() {
+    // create a client, whose requests live only for a second
+    let client = IpdisClient::infer().await.with_policy(RequestPolicy {
+        max_lifetime: Duration::seconds(1),
+        ..Default::default()
+    });
+    let ipiis: &IpiisClient = client.as_ref();
+    let account = ipiis.account_ref();
+
+    // create a sample word to be stored
+    let word = common::create_word("ipdis-api-postgres-test-long-lived", "hello world");
+    let parent = Hash::with_str("");
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.key.namespace)
+        .await
+        .unwrap();
+
+    // sign as guarantee
+    let word = ipiis.sign_owned(*account, word).unwrap();
+
+    // the records which never expire are accepted
+    let mut metadata = word.metadata.clone();
+    metadata.data.expiration_date = None;
+    client.policy().ensure_recent(&metadata).unwrap();
+
+    // the word is not limited by the lifetime of the requests
+    assert!(matches!(
+        client
+            .policy()
+            .ensure_alive(&word.metadata)
+            .map_err(IpdisError::from),
+        Err(IpdisError::Expired(_)),
+    ));
+    client.policy().ensure_recent(&word.metadata).unwrap();
+    client.put_word_unchecked(&parent, &word).await.unwrap();
+
+    // ensure that the replayed word is rejected
+    assert!(matches!(
+        client
+            .put_word_unchecked(&parent, &word)
+            .await
+            .map_err(IpdisError::from),
+        Err(IpdisError::Conflict(_)),
+    ));
+
+    // ensure that the word is read back after the lifetime of the requests
+    tokio::time::sleep(::std::time::Duration::from_secs(2)).await;
+    let word_from_ipdis = client
+        .get_word_latest_unchecked(None, &word.data.key)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&word_from_ipdis.data, &word.data);
+    assert_eq!(
+        client
+            .get_word_count_unchecked(None, &word.data.key, false)
+            .await
+            .unwrap(),
+        1,
+    );
+
+    // cleanup test data
+    client
+        .delete_word_all_unchecked(None, &word.data.key.namespace)
+        .await
+        .unwrap();
+}
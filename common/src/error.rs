@@ -0,0 +1,121 @@
+use bytecheck::CheckBytes;
+use ipis::core::anyhow::{anyhow, Error};
+use rkyv::{AlignedVec, Archive, Deserialize, Serialize};
+
+/// A failure of the IPDIS calls, which can be distinguished by the clients.
+///
+/// The errors are sent over IPIIS as their messages followed by their archived forms,
+/// so that the remote clients can restore the same variants with [`IpdisError::from`].
+/// The other errors are restored as [`IpdisError::Backend`].
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub enum IpdisError {
+    /// the accounts or the signatures are not trusted
+    Unauthenticated(String),
+    /// the request is out of its lifetime
+    Expired(String),
+    MalformedQuery(String),
+    NotFound(String),
+    /// the request collides with the existing records
+    Conflict(String),
+    /// the rate limit or the quota of the guarantee is exceeded
+    Exhausted(String),
+    /// the server cannot handle the request for now, e.g. shutting down
+    Unavailable(String),
+    /// the other failures, including the storages
+    Backend(String),
+}
+
+impl ::core::fmt::Display for IpdisError {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        write!(f, "{}: {}", self.kind(), self.message())
+    }
+}
+
+impl ::std::error::Error for IpdisError {}
+
+impl From<&Error> for IpdisError {
+    fn from(error: &Error) -> Self {
+        // the local error
+        if let Some(error) = error.downcast_ref::<Self>() {
+            return error.clone();
+        }
+
+        // the remote error, which carries its archived form
+        let message = error.to_string();
+        Self::from_remote(&message).unwrap_or(Self::Backend(message))
+    }
+}
+
+impl From<Error> for IpdisError {
+    fn from(error: Error) -> Self {
+        Self::from(&error)
+    }
+}
+
+impl IpdisError {
+    /// The tag of the archived form, which follows the message.
+    const ARCHIVED_TAG: &'static str = "\nipdis-error:";
+
+    /// Converts into the error to be sent over IPIIS, which carries the archived form.
+    pub fn into_remote(self) -> Error {
+        match ::rkyv::to_bytes::<_, 256>(&self) {
+            Ok(bytes) => {
+                let archived: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+                anyhow!("{self}{}{archived}", Self::ARCHIVED_TAG)
+            }
+            Err(_) => self.into(),
+        }
+    }
+
+    /// Restores the error from the archived form, which is the last one in the message.
+    fn from_remote(message: &str) -> Option<Self> {
+        let (_, archived) = message.rsplit_once(Self::ARCHIVED_TAG)?;
+        let archived = archived
+            .split(|c: char| !c.is_ascii_hexdigit())
+            .next()
+            .unwrap_or_default();
+        if archived.len() % 2 != 0 {
+            return None;
+        }
+
+        let bytes = (0..archived.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&archived[index..index + 2], 16))
+            .collect::<Result<Vec<_>, _>>()
+            .ok()?;
+
+        // the archived bytes should be aligned
+        let mut buf = AlignedVec::with_capacity(bytes.len());
+        buf.extend_from_slice(&bytes);
+
+        ::rkyv::from_bytes(&buf).ok()
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Unauthenticated(_) => "Unauthenticated",
+            Self::Expired(_) => "Expired",
+            Self::MalformedQuery(_) => "MalformedQuery",
+            Self::NotFound(_) => "NotFound",
+            Self::Conflict(_) => "Conflict",
+            Self::Exhausted(_) => "Exhausted",
+            Self::Unavailable(_) => "Unavailable",
+            Self::Backend(_) => "Backend",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Unauthenticated(message)
+            | Self::Expired(message)
+            | Self::MalformedQuery(message)
+            | Self::NotFound(message)
+            | Self::Conflict(message)
+            | Self::Exhausted(message)
+            | Self::Unavailable(message)
+            | Self::Backend(message) => message,
+        }
+    }
+}
@@ -3,27 +3,46 @@ use ipiis_common::{define_io, external_call, Ipiis, ServerResult};
 use ipis::{
     async_trait::async_trait,
     core::{
-        account::{AccountRef, GuaranteeSigned, GuarantorSigned},
+        account::{AccountRef, GuaranteeSigned, GuarantorSigned, Verifier},
         anyhow::{bail, Result},
+        chrono::Utc,
         data::Data,
         signed::IsSigned,
-        value::hash::Hash,
+        value::{chrono::DateTime, hash::Hash},
     },
     path::{DynPath, Path},
     word::{WordHash, WordKeyHash},
 };
 use rkyv::{Archive, Deserialize, Serialize};
 
+mod error;
+
+pub use self::error::IpdisError;
+
 #[async_trait]
 pub trait Ipdis {
-    async fn ensure_registered(&self, guarantee: &AccountRef, guarantor: &AccountRef)
-        -> Result<()>;
+    /// Ensures that the guarantee is registered to the guarantor,
+    /// and that its scope covers the role, and the namespace if given.
+    async fn ensure_registered(
+        &self,
+        guarantee: &AccountRef,
+        guarantor: &AccountRef,
+        role: GuaranteeRole,
+        namespace: Option<&Hash>,
+    ) -> Result<()>;
+
+    /// Checks whether the server is ready to handle the requests.
+    ///
+    /// Anyone can check it without being registered.
+    async fn get_health(&self) -> Result<Health>;
 
     async fn add_guarantee(&self, target: &Data<GuarantorSigned, AccountRef>) -> Result<()> {
         let guarantee = &target.metadata.guarantee.account;
         let guarantor = &target.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantee).await?;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(guarantee, guarantee, GuaranteeRole::Admin, None)
+            .await?;
+        self.ensure_registered(guarantee, guarantor, GuaranteeRole::Admin, None)
+            .await?;
 
         self.add_guarantee_unchecked(target).await
     }
@@ -33,33 +52,236 @@ pub trait Ipdis {
         guarantee: &Data<GuarantorSigned, AccountRef>,
     ) -> Result<()>;
 
+    async fn get_guarantee(
+        &self,
+        guarantee: &Data<GuaranteeSigned, AccountRef>,
+    ) -> Result<Data<GuarantorSigned, AccountRef>> {
+        let account = &guarantee.metadata.guarantee.account;
+        let guarantor = &guarantee.metadata.data.guarantor;
+
+        self.ensure_registered(account, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // only the guarantee itself or the guarantor can perform it
+        if account != &guarantee.data && account != guarantor {
+            bail!(IpdisError::Unauthenticated(
+                "failed to authorize the guarantee".into(),
+            ))
+        }
+
+        self.get_guarantee_unchecked(&guarantee.data).await
+    }
+
+    /// Raises [`IpdisError::NotFound`] if the guarantee is not registered.
+    async fn get_guarantee_unchecked(
+        &self,
+        guarantee: &AccountRef,
+    ) -> Result<Data<GuarantorSigned, AccountRef>> {
+        let query = GetGuarantees {
+            guarantee: Some(*guarantee),
+            start_index: 0,
+            end_index: 1,
+        };
+
+        self.get_guarantee_many_unchecked(&query)
+            .await?
+            .pop()
+            .ok_or_else(|| IpdisError::NotFound(format!("no such guarantee: {guarantee}")).into())
+    }
+
+    async fn get_guarantee_many(
+        &self,
+        query: &Data<GuaranteeSigned, GetGuarantees>,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        let account = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+
+        self.ensure_registered(account, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // only the guarantee itself or the guarantor can perform it
+        if query.data.guarantee.as_ref() != Some(account) && account != guarantor {
+            bail!(IpdisError::Unauthenticated(
+                "failed to authorize the guarantee".into(),
+            ))
+        }
+
+        self.get_guarantee_many_unchecked(&query.data).await
+    }
+
+    /// Returns the guarantees which are neither expired nor revoked, the latest first.
+    async fn get_guarantee_many_unchecked(
+        &self,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>>;
+
+    async fn delete_guarantee(&self, guarantee: &Data<GuaranteeSigned, AccountRef>) -> Result<()> {
+        let account = &guarantee.metadata.guarantee.account;
+        let guarantor = &guarantee.metadata.data.guarantor;
+        self.ensure_registered(account, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // only the guarantee itself or the guarantor can perform it
+        if account != &guarantee.data && account != guarantor {
+            bail!(IpdisError::Unauthenticated(
+                "failed to authorize the guarantee".into(),
+            ))
+        }
+
+        self.delete_guarantee_unchecked(&guarantee.data).await
+    }
+
+    /// Erases the guarantees of the account, along with their scopes.
+    ///
+    /// Unlike revoking, nothing is left on record, so this is for forgetting the account,
+    /// while a revocation withdraws the guarantee and keeps the signed proof of it.
+    async fn delete_guarantee_unchecked(&self, guarantee: &AccountRef) -> Result<()>;
+
+    async fn revoke_guarantee(&self, revocation: &Data<GuaranteeSigned, AccountRef>) -> Result<()> {
+        let account = &revocation.metadata.guarantee.account;
+        let guarantor = &revocation.metadata.data.guarantor;
+        self.ensure_registered(account, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        // only the guarantee itself or the guarantor can perform it
+        if account != &revocation.data && account != guarantor {
+            bail!(IpdisError::Unauthenticated(
+                "failed to authorize the guarantee".into(),
+            ))
+        }
+
+        self.revoke_guarantee_unchecked(revocation).await
+    }
+
+    /// Stores the signed revocation of the guarantee.
+    ///
+    /// Unlike deleting, the revocation is kept as a record,
+    /// so that the guarantees created before it are no longer accepted.
+    async fn revoke_guarantee_unchecked(
+        &self,
+        revocation: &Data<GuaranteeSigned, AccountRef>,
+    ) -> Result<()>;
+
+    async fn set_guarantee_scope(
+        &self,
+        scope: &Data<GuaranteeSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        let account = &scope.metadata.guarantee.account;
+        let guarantor = &scope.metadata.data.guarantor;
+        self.ensure_registered(account, guarantor, GuaranteeRole::Admin, None)
+            .await?;
+
+        self.set_guarantee_scope_unchecked(scope).await
+    }
+
+    /// Stores the signed scope of the guarantee, replacing the former ones.
+    async fn set_guarantee_scope_unchecked(
+        &self,
+        scope: &Data<GuaranteeSigned, GuaranteeScope>,
+    ) -> Result<()>;
+
     async fn get_dyn_path<Path>(
         &self,
         path: &Data<GuaranteeSigned, DynPath<Path>>,
-    ) -> Result<Option<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>>
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
     where
         Path: Copy + Send + Sync,
     {
         let guarantee = &path.metadata.guarantee.account;
         let guarantor = &path.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&path.data.namespace),
+        )
+        .await?;
 
         self.get_dyn_path_unchecked(Some(guarantee), &path.data)
             .await
     }
 
+    /// Raises [`IpdisError::NotFound`] if the dynamic path is not put or expired.
     async fn get_dyn_path_unchecked<Path>(
         &self,
         guarantee: Option<&AccountRef>,
         path: &DynPath<Path>,
-    ) -> Result<Option<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>>
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
+    where
+        Path: Copy + Send + Sync;
+
+    async fn get_dyn_path_at<Path>(
+        &self,
+        path: &Data<GuaranteeSigned, DynPath<Path>>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
+    where
+        Path: Copy + Send + Sync,
+    {
+        let guarantee = &path.metadata.guarantee.account;
+        let guarantor = &path.metadata.data.guarantor;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&path.data.namespace),
+        )
+        .await?;
+
+        self.get_dyn_path_at_unchecked(Some(guarantee), &path.data, timestamp)
+            .await
+    }
+
+    /// Resolves the dynamic path as it was at the given instant.
+    ///
+    /// The newest record created until then wins, if it had not been expired.
+    /// Raises [`IpdisError::NotFound`] if there was no such record.
+    async fn get_dyn_path_at_unchecked<Path>(
+        &self,
+        guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
     where
         Path: Copy + Send + Sync;
 
+    async fn get_dyn_path_many(
+        &self,
+        query: &Data<GuaranteeSigned, GetDynPaths>,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>, SignedCursor>> {
+        let guarantee = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&query.data.path.namespace),
+        )
+        .await?;
+
+        self.get_dyn_path_many_unchecked(Some(guarantee), &query.data)
+            .await
+    }
+
+    /// Returns all the versions of the dynamic path, including the expired ones.
+    ///
+    /// The latest put comes first.
+    async fn get_dyn_path_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>, SignedCursor>>;
+
     async fn put_dyn_path(&self, path: &Data<GuaranteeSigned, DynPath<Path>>) -> Result<()> {
         let guarantee = &path.metadata.guarantee.account;
         let guarantor = &path.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadWrite,
+            Some(&path.data.namespace),
+        )
+        .await?;
 
         self.put_dyn_path_unchecked(path).await
     }
@@ -69,13 +291,46 @@ pub trait Ipdis {
         path: &Data<GuaranteeSigned, DynPath<Path>>,
     ) -> Result<()>;
 
+    async fn delete_dyn_path_all(
+        &self,
+        query: &Data<GuaranteeSigned, DeleteDynPaths>,
+    ) -> Result<()> {
+        let guarantee = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadWrite,
+            Some(&query.data.namespace),
+        )
+        .await?;
+
+        self.delete_dyn_path_all_unchecked(Some(guarantee), &query.data.namespace)
+            .await
+    }
+
+    /// Deletes the dynamic paths in the namespace signed by the guarantee.
+    ///
+    /// If the guarantee is not given, the paths of all the guarantees are deleted.
+    async fn delete_dyn_path_all_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()>;
+
     async fn get_word_latest(
         &self,
         word: &Data<GuaranteeSigned, WordKeyHash>,
     ) -> Result<Option<Data<GuarantorSigned, WordHash>>> {
         let guarantee = &word.metadata.guarantee.account;
         let guarantor = &word.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&word.data.namespace),
+        )
+        .await?;
 
         self.get_word_latest_unchecked(Some(guarantee), &word.data)
             .await
@@ -89,22 +344,29 @@ pub trait Ipdis {
         let query = GetWords {
             word: *word,
             parent: GetWordsParent::None,
+            cursor: None,
             start_index: 0,
             end_index: 1,
         };
 
         self.get_word_many_unchecked(guarantee, &query)
             .await
-            .map(|mut records| records.pop())
+            .map(|mut page| page.items.pop())
     }
 
     async fn get_word_many(
         &self,
         query: &Data<GuaranteeSigned, GetWords>,
-    ) -> Result<Vec<Data<GuarantorSigned, WordHash>>> {
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>, SignedCursor>> {
         let guarantee = &query.metadata.guarantee.account;
         let guarantor = &query.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&query.data.word.namespace),
+        )
+        .await?;
 
         self.get_word_many_unchecked(Some(guarantee), &query.data)
             .await
@@ -114,7 +376,7 @@ pub trait Ipdis {
         &self,
         guarantee: Option<&AccountRef>,
         query: &GetWords,
-    ) -> Result<Vec<Data<GuarantorSigned, WordHash>>>;
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>, SignedCursor>>;
 
     async fn get_word_count(
         &self,
@@ -123,7 +385,13 @@ pub trait Ipdis {
     ) -> Result<u32> {
         let guarantee = &word.metadata.guarantee.account;
         let guarantor = &word.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&word.data.namespace),
+        )
+        .await?;
 
         self.get_word_count_unchecked(Some(guarantee), &word.data, owned)
             .await
@@ -139,22 +407,29 @@ pub trait Ipdis {
             word: *word,
             parent: false,
             owned,
+            cursor: None,
             start_index: 0,
             end_index: 1,
         };
 
         self.get_word_count_many_unchecked(guarantee, &query)
             .await
-            .map(|mut records| records.pop().map(|record| record.count).unwrap_or(0))
+            .map(|mut page| page.items.pop().map(|record| record.count).unwrap_or(0))
     }
 
     async fn get_word_count_many(
         &self,
         query: &Data<GuaranteeSigned, GetWordsCounts>,
-    ) -> Result<Vec<GetWordsCountsOutput>> {
+    ) -> Result<Page<GetWordsCountsOutput, SignedCursor>> {
         let guarantee = &query.metadata.guarantee.account;
         let guarantor = &query.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&query.data.word.namespace),
+        )
+        .await?;
 
         self.get_word_count_many_unchecked(Some(guarantee), &query.data)
             .await
@@ -164,12 +439,50 @@ pub trait Ipdis {
         &self,
         guarantee: Option<&AccountRef>,
         query: &GetWordsCounts,
-    ) -> Result<Vec<GetWordsCountsOutput>>;
+    ) -> Result<Page<GetWordsCountsOutput, SignedCursor>>;
+
+    async fn get_namespace_stats(
+        &self,
+        query: &Data<GuaranteeSigned, GetNamespaceStats>,
+    ) -> Result<NamespaceStats> {
+        let guarantee = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadOnly,
+            Some(&query.data.namespace),
+        )
+        .await?;
+
+        self.get_namespace_stats_unchecked(&query.data.namespace)
+            .await
+    }
+
+    async fn get_namespace_stats_unchecked(&self, namespace: &Hash) -> Result<NamespaceStats>;
+
+    async fn get_quota(&self, query: &Data<GuaranteeSigned, GetQuota>) -> Result<Quota> {
+        let guarantee = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+        self.ensure_registered(guarantee, guarantor, GuaranteeRole::ReadOnly, None)
+            .await?;
+
+        self.get_quota_unchecked(guarantee).await
+    }
+
+    /// Returns the limits of the guarantee, and how much of them have been used.
+    async fn get_quota_unchecked(&self, guarantee: &AccountRef) -> Result<Quota>;
 
     async fn put_word(&self, parent: &Hash, word: &Data<GuaranteeSigned, WordHash>) -> Result<()> {
         let guarantee = &word.metadata.guarantee.account;
         let guarantor = &word.metadata.data.guarantor;
-        self.ensure_registered(guarantee, guarantor).await?;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadWrite,
+            Some(&word.data.key.namespace),
+        )
+        .await?;
 
         self.put_word_unchecked(parent, word).await
     }
@@ -179,6 +492,63 @@ pub trait Ipdis {
         parent: &Hash,
         word: &Data<GuaranteeSigned, WordHash>,
     ) -> Result<()>;
+
+    async fn put_word_many(
+        &self,
+        words: &[(Hash, Data<GuaranteeSigned, WordHash>)],
+    ) -> Result<Vec<PutWordsError>> {
+        let mut registered = vec![];
+        for (_, word) in words {
+            let guarantee = &word.metadata.guarantee.account;
+            let guarantor = &word.metadata.data.guarantor;
+            let namespace = &word.data.key.namespace;
+            if !registered.contains(&(guarantee, guarantor, namespace)) {
+                self.ensure_registered(
+                    guarantee,
+                    guarantor,
+                    GuaranteeRole::ReadWrite,
+                    Some(namespace),
+                )
+                .await?;
+                registered.push((guarantee, guarantor, namespace));
+            }
+        }
+
+        self.put_word_many_unchecked(None, words).await
+    }
+
+    /// Puts the words at once, and returns the ones which have been rejected.
+    ///
+    /// If the guarantee is given, the words signed by the others are rejected.
+    async fn put_word_many_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        words: &[(Hash, Data<GuaranteeSigned, WordHash>)],
+    ) -> Result<Vec<PutWordsError>>;
+
+    async fn delete_word_all(&self, query: &Data<GuaranteeSigned, DeleteWords>) -> Result<()> {
+        let guarantee = &query.metadata.guarantee.account;
+        let guarantor = &query.metadata.data.guarantor;
+        self.ensure_registered(
+            guarantee,
+            guarantor,
+            GuaranteeRole::ReadWrite,
+            Some(&query.data.namespace),
+        )
+        .await?;
+
+        self.delete_word_all_unchecked(Some(guarantee), &query.data.namespace)
+            .await
+    }
+
+    /// Deletes the words in the namespace signed by the guarantee.
+    ///
+    /// If the guarantee is not given, the words of all the guarantees are deleted.
+    async fn delete_word_all_unchecked(
+        &self,
+        guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()>;
 }
 
 #[async_trait]
@@ -190,15 +560,43 @@ where
         &self,
         guarantee: &AccountRef,
         _guarantor: &AccountRef,
+        _role: GuaranteeRole,
+        _namespace: Option<&Hash>,
     ) -> Result<()> {
         let guarantee_now = self.account_ref();
         if guarantee != guarantee_now {
-            bail!("failed to authenticate the guarantee")
+            bail!(IpdisError::Unauthenticated(
+                "failed to authenticate the guarantee".into(),
+            ))
         }
 
         Ok(())
     }
 
+    async fn get_health(&self) -> Result<Health> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (health,) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => HealthGet,
+            sign: self.sign_owned(target, GetHealth)?,
+            inputs: { },
+            outputs: { health, },
+        );
+
+        // unpack response
+        health.verify(None)?;
+        if health.metadata.guarantor.account != target {
+            bail!(IpdisError::Unauthenticated(
+                "unexpected guarantor: the health is not signed by the server".into(),
+            ))
+        }
+        Ok(health.data)
+    }
+
     async fn add_guarantee_unchecked(
         &self,
         guarantee: &Data<GuarantorSigned, AccountRef>,
@@ -220,11 +618,92 @@ where
         Ok(())
     }
 
+    async fn get_guarantee_many_unchecked(
+        &self,
+        query: &GetGuarantees,
+    ) -> Result<Vec<Data<GuarantorSigned, AccountRef>>> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (guarantees,) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => GuaranteeGetMany,
+            sign: self.sign_owned(target, *query)?,
+            inputs: { },
+            outputs: { guarantees, },
+        );
+
+        // unpack response
+        Ok(guarantees)
+    }
+
+    async fn delete_guarantee_unchecked(&self, guarantee: &AccountRef) -> Result<()> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => GuaranteeDelete,
+            sign: self.sign_owned(target, *guarantee)?,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn revoke_guarantee_unchecked(
+        &self,
+        revocation: &Data<GuaranteeSigned, AccountRef>,
+    ) -> Result<()> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => GuaranteeRevoke,
+            sign: *revocation,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn set_guarantee_scope_unchecked(
+        &self,
+        scope: &Data<GuaranteeSigned, GuaranteeScope>,
+    ) -> Result<()> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => GuaranteeScopePut,
+            sign: scope.clone(),
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
     async fn get_dyn_path_unchecked<Path>(
         &self,
         _guarantee: Option<&AccountRef>,
         path: &DynPath<Path>,
-    ) -> Result<Option<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>>
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
     where
         Path: Copy + Send + Sync,
     {
@@ -245,75 +724,88 @@ where
         Ok(path)
     }
 
-    async fn put_dyn_path_unchecked(
+    async fn get_dyn_path_at_unchecked<Path>(
         &self,
-        path: &Data<GuaranteeSigned, DynPath<Path>>,
-    ) -> Result<()> {
+        _guarantee: Option<&AccountRef>,
+        path: &DynPath<Path>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Data<GuarantorSigned, DynPath<::ipis::path::Path>>>
+    where
+        Path: Copy + Send + Sync,
+    {
         // next target
         let target = self.get_account_primary(KIND.as_ref()).await?;
 
+        // pack data
+        let query = GetDynPathAt {
+            path: (*path).remove_path(),
+            timestamp: *timestamp,
+        };
+
         // external call
-        external_call!(
+        let (path,) = external_call!(
             client: self,
             target: KIND.as_ref() => &target,
-            request: crate::io => DynPathPut,
-            sign: *path,
+            request: crate::io => DynPathGetAt,
+            sign: self.sign_owned(target, query)?,
             inputs: { },
-            outputs: { },
+            outputs: { path, },
         );
 
         // unpack response
-        Ok(())
+        Ok(path)
     }
 
-    async fn get_word_many_unchecked(
+    async fn get_dyn_path_many_unchecked(
         &self,
         _guarantee: Option<&AccountRef>,
-        query: &GetWords,
-    ) -> Result<Vec<Data<GuarantorSigned, WordHash>>> {
+        query: &GetDynPaths,
+    ) -> Result<Page<Data<GuarantorSigned, DynPath<Path>>, SignedCursor>> {
         // next target
         let target = self.get_account_primary(KIND.as_ref()).await?;
 
         // external call
-        let (words,) = external_call!(
+        let (paths, cursor) = external_call!(
             client: self,
             target: KIND.as_ref() => &target,
-            request: crate::io => WordGetMany,
+            request: crate::io => DynPathGetMany,
             sign: self.sign_owned(target, *query)?,
             inputs: { },
-            outputs: { words, },
+            outputs: { paths, cursor, },
         );
 
         // unpack response
-        Ok(words)
+        Ok(Page {
+            items: paths,
+            cursor,
+        })
     }
 
-    async fn get_word_count_many_unchecked(
+    async fn put_dyn_path_unchecked(
         &self,
-        _guarantee: Option<&AccountRef>,
-        query: &GetWordsCounts,
-    ) -> Result<Vec<GetWordsCountsOutput>> {
+        path: &Data<GuaranteeSigned, DynPath<Path>>,
+    ) -> Result<()> {
         // next target
         let target = self.get_account_primary(KIND.as_ref()).await?;
 
         // external call
-        let (counts,) = external_call!(
+        external_call!(
             client: self,
             target: KIND.as_ref() => &target,
-            request: crate::io => WordCountGetMany,
-            sign: self.sign_owned(target, *query)?,
+            request: crate::io => DynPathPut,
+            sign: *path,
             inputs: { },
-            outputs: { counts, },
+            outputs: { },
         );
 
         // unpack response
-        Ok(counts)
+        Ok(())
     }
 
-    async fn put_word_unchecked(
+    async fn delete_dyn_path_all_unchecked(
         &self,
-        parent: &Hash,
-        word: &Data<GuaranteeSigned, WordHash>,
+        _guarantee: Option<&AccountRef>,
+        namespace: &Hash,
     ) -> Result<()> {
         // next target
         let target = self.get_account_primary(KIND.as_ref()).await?;
@@ -322,36 +814,248 @@ where
         external_call!(
             client: self,
             target: KIND.as_ref() => &target,
-            request: crate::io => WordPut,
-            sign: *word,
-            inputs: {
-                parent: *parent,
-            },
+            request: crate::io => DynPathDelete,
+            sign: self.sign_owned(target, DeleteDynPaths { namespace: *namespace })?,
+            inputs: { },
             outputs: { },
         );
 
         // unpack response
         Ok(())
     }
-}
 
-define_io! {
-    GuaranteePut {
-        inputs: { },
-        input_sign: Data<GuaranteeSigned, Data<GuarantorSigned, AccountRef>>,
-        outputs: { },
-        output_sign: Data<GuarantorSigned, Data<GuarantorSigned, AccountRef>>,
-        generics: { },
-    },
-    DynPathGet {
+    async fn get_word_many_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWords,
+    ) -> Result<Page<Data<GuarantorSigned, WordHash>, SignedCursor>> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (words, cursor) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordGetMany,
+            sign: self.sign_owned(target, *query)?,
+            inputs: { },
+            outputs: { words, cursor, },
+        );
+
+        // unpack response
+        Ok(Page {
+            items: words,
+            cursor,
+        })
+    }
+
+    async fn get_word_count_many_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        query: &GetWordsCounts,
+    ) -> Result<Page<GetWordsCountsOutput, SignedCursor>> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (counts, cursor) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordCountGetMany,
+            sign: self.sign_owned(target, *query)?,
+            inputs: { },
+            outputs: { counts, cursor, },
+        );
+
+        // unpack response
+        Ok(Page {
+            items: counts,
+            cursor,
+        })
+    }
+
+    async fn get_namespace_stats_unchecked(&self, namespace: &Hash) -> Result<NamespaceStats> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (stats,) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => NamespaceStatsGet,
+            sign: self.sign_owned(target, GetNamespaceStats { namespace: *namespace })?,
+            inputs: { },
+            outputs: { stats, },
+        );
+
+        // unpack response
+        Ok(stats)
+    }
+
+    async fn get_quota_unchecked(&self, _guarantee: &AccountRef) -> Result<Quota> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (quota,) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => QuotaGet,
+            sign: self.sign_owned(target, GetQuota)?,
+            inputs: { },
+            outputs: { quota, },
+        );
+
+        // unpack response
+        Ok(quota)
+    }
+
+    async fn put_word_unchecked(
+        &self,
+        parent: &Hash,
+        word: &Data<GuaranteeSigned, WordHash>,
+    ) -> Result<()> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordPut,
+            sign: *word,
+            inputs: {
+                parent: *parent,
+            },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+
+    async fn put_word_many_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        words: &[(Hash, Data<GuaranteeSigned, WordHash>)],
+    ) -> Result<Vec<PutWordsError>> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        let (errors,) = external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordPutMany,
+            sign: self.sign_owned(target, PutWords { count: words.len().try_into()? })?,
+            inputs: {
+                words: words.to_vec(),
+            },
+            outputs: { errors, },
+        );
+
+        // unpack response
+        Ok(errors)
+    }
+
+    async fn delete_word_all_unchecked(
+        &self,
+        _guarantee: Option<&AccountRef>,
+        namespace: &Hash,
+    ) -> Result<()> {
+        // next target
+        let target = self.get_account_primary(KIND.as_ref()).await?;
+
+        // external call
+        external_call!(
+            client: self,
+            target: KIND.as_ref() => &target,
+            request: crate::io => WordDelete,
+            sign: self.sign_owned(target, DeleteWords { namespace: *namespace })?,
+            inputs: { },
+            outputs: { },
+        );
+
+        // unpack response
+        Ok(())
+    }
+}
+
+define_io! {
+    HealthGet {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetHealth>,
+        outputs: {
+            health: Data<GuarantorSigned, Health>,
+        },
+        output_sign: Data<GuarantorSigned, GetHealth>,
+        generics: { },
+    },
+    GuaranteePut {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, Data<GuarantorSigned, AccountRef>>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, Data<GuarantorSigned, AccountRef>>,
+        generics: { },
+    },
+    GuaranteeGetMany {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetGuarantees>,
+        outputs: {
+            guarantees: Vec<Data<GuarantorSigned, AccountRef>>,
+        },
+        output_sign: Data<GuarantorSigned, GetGuarantees>,
+        generics: { },
+    },
+    GuaranteeDelete {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, AccountRef>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, AccountRef>,
+        generics: { },
+    },
+    GuaranteeRevoke {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, AccountRef>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, AccountRef>,
+        generics: { },
+    },
+    GuaranteeScopePut {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GuaranteeScope>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, GuaranteeScope>,
+        generics: { },
+    },
+    DynPathGet {
         inputs: { },
         input_sign: Data<GuaranteeSigned, DynPath<()>>,
         outputs: {
-            path: Option<Data<GuarantorSigned, DynPath<Path>>>,
+            path: Data<GuarantorSigned, DynPath<Path>>,
         },
         output_sign: Data<GuarantorSigned, DynPath<()>>,
         generics: { },
     },
+    DynPathGetAt {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetDynPathAt>,
+        outputs: {
+            path: Data<GuarantorSigned, DynPath<Path>>,
+        },
+        output_sign: Data<GuarantorSigned, GetDynPathAt>,
+        generics: { },
+    },
+    DynPathGetMany {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetDynPaths>,
+        outputs: {
+            paths: Vec<Data<GuarantorSigned, DynPath<Path>>>,
+            cursor: Option<SignedCursor>,
+        },
+        output_sign: Data<GuarantorSigned, GetDynPaths>,
+        generics: { },
+    },
     DynPathPut {
         inputs: { },
         input_sign: Data<GuaranteeSigned, DynPath<Path>>,
@@ -359,11 +1063,19 @@ define_io! {
         output_sign: Data<GuarantorSigned, DynPath<Path>>,
         generics: { },
     },
+    DynPathDelete {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, DeleteDynPaths>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, DeleteDynPaths>,
+        generics: { },
+    },
     WordGetMany {
         inputs: { },
         input_sign: Data<GuaranteeSigned, GetWords>,
         outputs: {
             words: Vec<Data<GuarantorSigned, WordHash>>,
+            cursor: Option<SignedCursor>,
         },
         output_sign: Data<GuarantorSigned, GetWords>,
         generics: { },
@@ -377,24 +1089,133 @@ define_io! {
         output_sign: Data<GuarantorSigned, WordHash>,
         generics: { },
     },
+    WordPutMany {
+        inputs: {
+            words: Vec<(Hash, Data<GuaranteeSigned, WordHash>)>,
+        },
+        input_sign: Data<GuaranteeSigned, PutWords>,
+        outputs: {
+            errors: Vec<PutWordsError>,
+        },
+        output_sign: Data<GuarantorSigned, PutWords>,
+        generics: { },
+    },
+    WordDelete {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, DeleteWords>,
+        outputs: { },
+        output_sign: Data<GuarantorSigned, DeleteWords>,
+        generics: { },
+    },
     WordCountGetMany {
         inputs: { },
         input_sign: Data<GuaranteeSigned, GetWordsCounts>,
         outputs: {
             counts: Vec<GetWordsCountsOutput>,
+            cursor: Option<SignedCursor>,
         },
         output_sign: Data<GuarantorSigned, GetWordsCounts>,
         generics: { },
     },
+    NamespaceStatsGet {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetNamespaceStats>,
+        outputs: {
+            stats: NamespaceStats,
+        },
+        output_sign: Data<GuarantorSigned, GetNamespaceStats>,
+        generics: { },
+    },
+    QuotaGet {
+        inputs: { },
+        input_sign: Data<GuaranteeSigned, GetQuota>,
+        outputs: {
+            quota: Quota,
+        },
+        output_sign: Data<GuarantorSigned, GetQuota>,
+        generics: { },
+    },
 }
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetHealth;
+
+impl IsSigned for GetHealth {}
+
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Health {
+    /// the account of the server
+    pub account: AccountRef,
+    /// the failure of connecting to the storage, if any
+    pub storage_error: Option<String>,
+    /// the number of the migrations which have not been applied to the storage yet
+    pub pending_migrations: u32,
+}
+
+impl IsSigned for Health {}
+
+impl Health {
+    pub fn is_ready(&self) -> bool {
+        self.storage_error.is_none() && self.pending_migrations == 0
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetGuarantees {
+    /// all the guarantees of the guarantor if not given
+    pub guarantee: Option<AccountRef>,
+    /// inclusive left bound
+    pub start_index: u32,
+    /// exclusive right bound
+    pub end_index: u32,
+}
+
+impl IsSigned for GetGuarantees {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetDynPathAt {
+    pub path: DynPath<()>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl IsSigned for GetDynPathAt {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetDynPaths {
+    pub path: DynPath<()>,
+    /// inclusive lower bound of the created date
+    pub since: Option<DateTime<Utc>>,
+    /// inclusive upper bound of the created date
+    pub until: Option<DateTime<Utc>>,
+    /// continue after the last seen record if given
+    pub cursor: Option<SignedCursor>,
+    /// inclusive left bound (counted from the cursor)
+    pub start_index: u32,
+    /// exclusive right bound
+    pub end_index: u32,
+}
+
+impl IsSigned for GetDynPaths {}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
 #[archive(compare(PartialEq))]
 #[archive_attr(derive(CheckBytes, Debug, PartialEq))]
 pub struct GetWords {
     pub word: WordKeyHash,
     pub parent: GetWordsParent,
-    /// inclusive left bound
+    /// continue after the last seen record if given
+    pub cursor: Option<SignedCursor>,
+    /// inclusive left bound (counted from the cursor)
     pub start_index: u32,
     /// exclusive right bound
     pub end_index: u32,
@@ -417,7 +1238,9 @@ pub struct GetWordsCounts {
     pub word: WordKeyHash,
     pub parent: bool,
     pub owned: bool,
-    /// inclusive left bound
+    /// continue after the last seen record if given
+    pub cursor: Option<SignedCursor>,
+    /// inclusive left bound (counted from the cursor)
     pub start_index: u32,
     /// exclusive right bound
     pub end_index: u32,
@@ -445,6 +1268,259 @@ pub struct GetWordKeyHash {
 
 impl IsSigned for GetWordKeyHash {}
 
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetNamespaceStats {
+    pub namespace: Hash,
+}
+
+impl IsSigned for GetNamespaceStats {}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct NamespaceStats {
+    /// the number of all the word records
+    pub total_words: u64,
+    /// the number of the words regardless of their kinds and parents
+    pub distinct_words: u64,
+    /// the number of the guarantees who have put the words
+    pub distinct_guarantees: u64,
+}
+
+impl IsSigned for NamespaceStats {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GetQuota;
+
+impl IsSigned for GetQuota {}
+
+/// The limits of a guarantee, each of which is unlimited if not given.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Quota {
+    /// the number of the requests refilled per second
+    pub rate_per_sec: Option<u32>,
+    /// the number of the requests which can be sent at once
+    pub rate_burst: Option<u32>,
+    /// the number of the requests which can be sent right now, by the most used IO call
+    pub rate_remaining: Option<u32>,
+    pub max_words: Option<u64>,
+    pub words: u64,
+    pub max_dyn_paths: Option<u64>,
+    pub dyn_paths: u64,
+}
+
+impl IsSigned for Quota {}
+
+/// The calls a guarantee may perform, each of which includes the former ones.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq, Eq))]
+pub enum GuaranteeRole {
+    ReadOnly,
+    ReadWrite,
+    /// may add the guarantees and set their scopes for the others
+    Admin,
+}
+
+impl IsSigned for GuaranteeRole {}
+
+impl ::core::fmt::Display for GuaranteeRole {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        match self {
+            Self::ReadOnly => write!(f, "read_only"),
+            Self::ReadWrite => write!(f, "read_write"),
+            Self::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+impl ::core::str::FromStr for GuaranteeRole {
+    type Err = ::ipis::core::anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "read_only" => Ok(Self::ReadOnly),
+            "read_write" => Ok(Self::ReadWrite),
+            "admin" => Ok(Self::Admin),
+            _ => bail!("unknown guarantee role: {s}"),
+        }
+    }
+}
+
+/// The calls and the namespaces which a guarantee is allowed to access.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct GuaranteeScope {
+    pub account: AccountRef,
+    pub role: GuaranteeRole,
+    /// all the namespaces if not given
+    pub namespaces: Option<Vec<Hash>>,
+}
+
+impl IsSigned for GuaranteeScope {}
+
+impl GuaranteeScope {
+    /// The scope of the guarantees which have not been given any,
+    /// which can read and write all the namespaces.
+    pub fn with_account(account: AccountRef) -> Self {
+        Self {
+            account,
+            role: GuaranteeRole::ReadWrite,
+            namespaces: None,
+        }
+    }
+
+    /// Ensures that the scope covers the role, and the namespace if given.
+    pub fn ensure_allowed(&self, role: GuaranteeRole, namespace: Option<&Hash>) -> Result<()> {
+        if self.role < role {
+            bail!(IpdisError::Unauthenticated(format!(
+                "failed to authorize the guarantee: {role} is required, but {} is given",
+                self.role,
+            )))
+        }
+
+        if let (Some(namespaces), Some(namespace)) = (&self.namespaces, namespace) {
+            if !namespaces.contains(namespace) {
+                bail!(IpdisError::Unauthenticated(
+                    "failed to authorize the guarantee: the namespace is not allowed".into(),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Ensures that the given scope is not wider than this one.
+    pub fn ensure_covers(&self, scope: &Self) -> Result<()> {
+        if self.role < scope.role {
+            bail!(IpdisError::Unauthenticated(format!(
+                "failed to authorize the scope: {} cannot give {}",
+                self.role, scope.role,
+            )))
+        }
+
+        if let Some(namespaces) = &self.namespaces {
+            let is_covered = match &scope.namespaces {
+                Some(scope_namespaces) => scope_namespaces
+                    .iter()
+                    .all(|namespace| namespaces.contains(namespace)),
+                None => false,
+            };
+            if !is_covered {
+                bail!(IpdisError::Unauthenticated(
+                    "failed to authorize the scope: the namespaces are not allowed".into(),
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A position of the last seen record to continue the pagination from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct Cursor {
+    id: u64,
+}
+
+impl IsSigned for Cursor {}
+
+impl Cursor {
+    pub fn with_id(id: u64) -> Self {
+        Self { id }
+    }
+
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+}
+
+/// A cursor signed by the server, which gave it.
+///
+/// The clients should treat it as opaque, and pass it back as it is.
+pub type SignedCursor = Data<GuaranteeSigned, Cursor>;
+
+/// A page of the records, followed by the cursor of the next page.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Page<T, C = Cursor> {
+    pub items: Vec<T>,
+    /// `None` if there are no more records
+    pub cursor: Option<C>,
+}
+
+impl<T, C> Page<T, C> {
+    /// Replaces the cursor, e.g. to sign it.
+    pub fn try_map_cursor<D>(self, f: impl FnOnce(C) -> Result<D>) -> Result<Page<T, D>> {
+        Ok(Page {
+            items: self.items,
+            cursor: self.cursor.map(f).transpose()?,
+        })
+    }
+}
+
+impl<T> Page<T> {
+    /// Builds a page from the records and their ids, in the order of the pagination.
+    ///
+    /// The cursor is given only if the page is full.
+    pub fn with_ids(records: Vec<(u64, T)>, limit: u32) -> Self {
+        let cursor = if records.len() >= limit as usize {
+            records.last().map(|(id, _)| Cursor::with_id(*id))
+        } else {
+            None
+        };
+
+        Self {
+            items: records.into_iter().map(|(_, record)| record).collect(),
+            cursor,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct PutWords {
+    /// the number of the words to be put
+    pub count: u32,
+}
+
+impl IsSigned for PutWords {}
+
+/// A word which has been rejected while putting the words at once.
+#[derive(Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct PutWordsError {
+    /// the index of the word in the request
+    pub index: u32,
+    pub error: IpdisError,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct DeleteDynPaths {
+    pub namespace: Hash,
+}
+
+impl IsSigned for DeleteDynPaths {}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Archive, Serialize, Deserialize)]
+#[archive(compare(PartialEq))]
+#[archive_attr(derive(CheckBytes, Debug, PartialEq))]
+pub struct DeleteWords {
+    pub namespace: Hash,
+}
+
+impl IsSigned for DeleteWords {}
+
 ::ipis::lazy_static::lazy_static! {
     pub static ref KIND: Option<::ipis::core::value::hash::Hash> = Some(
         ::ipis::core::value::hash::Hash::with_str("__ipis__ipdis__"),
@@ -0,0 +1,212 @@
+use std::{fmt::Display, net::SocketAddr, path::Path, str::FromStr};
+
+use ipis::core::anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+/// The configuration of the runtime, which is overridden by the environment variables.
+///
+/// The modules infer their configuration from the environment variables,
+/// so the effective configuration is exported to them before starting.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub account: AccountConfig,
+    pub server: ServerConfig,
+    pub storage: StorageConfig,
+    pub policy: PolicyConfig,
+    pub quota: QuotaConfig,
+    pub logging: LoggingConfig,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AccountConfig {
+    /// the secret key of the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub me: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub primary_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub router_db: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metrics_address: Option<SocketAddr>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shutdown_timeout_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// one of `postgres`, `sqlite`, `sled` and `memory`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// may contain the password of the database
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub database_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub migrate: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_max_size: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_min_idle: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_connection_timeout_secs: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_idle_timeout_secs: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PolicyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_lifetime_secs: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clock_skew_secs: Option<i64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct QuotaConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_per_sec: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_burst: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_words: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_dyn_paths: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    /// the filter of the logs, e.g. `info` or `ipdis_api=debug`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<String>,
+}
+
+impl Config {
+    const REDACTED: &'static str = "<redacted>";
+
+    /// Loads the configuration file if given, and then overrides it with the environment variables.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let mut config: Self = match path {
+            Some(path) => {
+                let file = ::std::fs::read_to_string(path)
+                    .map_err(|e| anyhow!("failed to read the config {}: {e}", path.display()))?;
+                ::toml::from_str(&file)
+                    .map_err(|e| anyhow!("malformed config {}: {e}", path.display()))?
+            }
+            None => Default::default(),
+        };
+
+        config.visit(&mut Override)?;
+        Ok(config)
+    }
+
+    /// Exports the configuration to the environment variables, from which the modules infer it.
+    pub fn export(&mut self) -> Result<()> {
+        self.visit(&mut Export)
+    }
+
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        for secret in [&mut config.account.me, &mut config.storage.database_url] {
+            if secret.is_some() {
+                *secret = Some(Self::REDACTED.into());
+            }
+        }
+        config
+    }
+
+    fn visit(&mut self, visitor: &mut impl Visitor) -> Result<()> {
+        visitor.visit("ipis_account_me", &mut self.account.me)?;
+        visitor.visit(
+            "ipiis_account_primary_address",
+            &mut self.account.primary_address,
+        )?;
+        visitor.visit("ipiis_router_db", &mut self.account.router_db)?;
+        visitor.visit("ipiis_server_port", &mut self.server.port)?;
+        visitor.visit("ipdis_metrics_address", &mut self.server.metrics_address)?;
+        visitor.visit(
+            "ipdis_shutdown_timeout_secs",
+            &mut self.server.shutdown_timeout_secs,
+        )?;
+        visitor.visit("ipdis_api_storage", &mut self.storage.backend)?;
+        visitor.visit("DATABASE_URL", &mut self.storage.database_url)?;
+        visitor.visit("ipdis_api_migrate", &mut self.storage.migrate)?;
+        visitor.visit("ipdis_api_pool_max_size", &mut self.storage.pool_max_size)?;
+        visitor.visit("ipdis_api_pool_min_idle", &mut self.storage.pool_min_idle)?;
+        visitor.visit(
+            "ipdis_api_pool_connection_timeout_secs",
+            &mut self.storage.pool_connection_timeout_secs,
+        )?;
+        visitor.visit(
+            "ipdis_api_pool_idle_timeout_secs",
+            &mut self.storage.pool_idle_timeout_secs,
+        )?;
+        visitor.visit(
+            "ipdis_policy_max_lifetime_secs",
+            &mut self.policy.max_lifetime_secs,
+        )?;
+        visitor.visit(
+            "ipdis_policy_clock_skew_secs",
+            &mut self.policy.clock_skew_secs,
+        )?;
+        visitor.visit("ipdis_quota_rate_per_sec", &mut self.quota.rate_per_sec)?;
+        visitor.visit("ipdis_quota_rate_burst", &mut self.quota.rate_burst)?;
+        visitor.visit("ipdis_quota_max_words", &mut self.quota.max_words)?;
+        visitor.visit("ipdis_quota_max_dyn_paths", &mut self.quota.max_dyn_paths)?;
+        visitor.visit("RUST_LOG", &mut self.logging.level)?;
+        Ok(())
+    }
+}
+
+trait Visitor {
+    fn visit<T>(&mut self, key: &str, value: &mut Option<T>) -> Result<()>
+    where
+        T: FromStr + Display,
+        <T as FromStr>::Err: Display;
+}
+
+/// Overrides the values with the environment variables.
+struct Override;
+
+impl Visitor for Override {
+    fn visit<T>(&mut self, key: &str, value: &mut Option<T>) -> Result<()>
+    where
+        T: FromStr + Display,
+        <T as FromStr>::Err: Display,
+    {
+        if let Ok(var) = ::std::env::var(key) {
+            *value = Some(
+                var.parse()
+                    .map_err(|e| anyhow!("malformed environment variable {key}: {e}"))?,
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Exports the values to the environment variables.
+struct Export;
+
+impl Visitor for Export {
+    fn visit<T>(&mut self, key: &str, value: &mut Option<T>) -> Result<()>
+    where
+        T: FromStr + Display,
+        <T as FromStr>::Err: Display,
+    {
+        if let Some(value) = value {
+            ::std::env::set_var(key, value.to_string());
+        }
+        Ok(())
+    }
+}
@@ -1,7 +1,184 @@
-use ipdis_api::server::IpdisServer;
-use ipis::{env::Infer, tokio};
+mod config;
+
+use std::{
+    net::{Ipv4Addr, SocketAddr},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use ipdis_api::{
+    common::{Health, Ipdis, KIND},
+    server::IpdisServer,
+    storage::{IpdisStorage, Storage},
+};
+use ipiis_api::{client::IpiisClient, common::Ipiis};
+use ipis::{
+    core::{account::Account, anyhow::Result},
+    env::{infer, Infer},
+    tokio::{self, signal},
+};
+
+use crate::config::Config;
+
+fn main() -> ExitCode {
+    let mut args = ::std::env::args().skip(1);
+    let mut check_only = false;
+    let mut check_storage_only = false;
+    let mut print_config = false;
+    let mut config_path: Option<PathBuf> = ::std::env::var_os("ipdis_config").map(Into::into);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--check" => check_only = true,
+            "--check-storage" => check_storage_only = true,
+            "--print-config" => print_config = true,
+            "--config" => match args.next() {
+                Some(path) => config_path = Some(path.into()),
+                None => {
+                    eprintln!("missing the path of --config");
+                    return ExitCode::FAILURE;
+                }
+            },
+            _ => {
+                eprintln!("unknown argument: {arg}");
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+
+    // export the configuration before the runtime spawns any threads
+    let mut config = match Config::load(config_path.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("misconfigured: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if print_config {
+        return match ::toml::to_string_pretty(&config.redacted()) {
+            Ok(config) => {
+                print!("{config}");
+                ExitCode::SUCCESS
+            }
+            Err(e) => {
+                eprintln!("failed to print the config: {e}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+    if let Err(e) = config.export() {
+        eprintln!("misconfigured: {e}");
+        return ExitCode::FAILURE;
+    }
+
+    if check_only {
+        check()
+    } else if check_storage_only {
+        check_storage()
+    } else {
+        run()
+    }
+}
 
 #[tokio::main]
-async fn main() {
-    IpdisServer::infer().await.run().await
+async fn run() -> ExitCode {
+    // init logger
+    ::ipis::logger::init_once();
+
+    let server = IpdisServer::infer().await;
+    let result = server.run_until(shutdown()).await;
+
+    // close the storage after draining the requests
+    drop(server);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("failed to run the server: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Waits for SIGINT, or SIGTERM on unix.
+async fn shutdown() {
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to listen SIGTERM")
+            .recv()
+            .await
+    };
+    #[cfg(not(unix))]
+    let terminate = ::std::future::pending::<Option<()>>();
+
+    tokio::select! {
+        _ = signal::ctrl_c() => {}
+        _ = terminate => {}
+    }
+}
+
+/// Checks whether the running server is ready, by asking its health over IPIIS.
+fn check() -> ExitCode {
+    // use a disposable router, as the server may hold its own one
+    let router_db = ::std::env::temp_dir().join(format!("ipdis-check-{}", ::std::process::id()));
+    ::std::env::set_var("ipiis_router_db", &router_db);
+
+    let result = get_health();
+    let _ = ::std::fs::remove_dir_all(&router_db);
+
+    match result {
+        Ok(health) if health.is_ready() => {
+            println!("ready: {health:?}");
+            ExitCode::SUCCESS
+        }
+        Ok(health) => {
+            eprintln!("not ready: {health:?}");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("not ready: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Sends `HealthGet` to the configured server account, which should sign the response.
+#[tokio::main]
+async fn get_health() -> Result<Health> {
+    let server: Account = infer("ipis_account_me")?;
+    let server = server.account_ref();
+    let port: u16 = infer("ipiis_server_port")?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+
+    let client = IpiisClient::genesis(None).await?;
+    client.set_account_primary(KIND.as_ref(), &server).await?;
+    client.set_address(KIND.as_ref(), &server, &address).await?;
+
+    client.get_health().await
+}
+
+/// Checks whether the storage is reachable and migrated, without migrating it.
+#[tokio::main]
+async fn check_storage() -> ExitCode {
+    let storage = match Storage::try_infer_verify_only().await {
+        Ok(storage) => storage,
+        Err(e) => {
+            eprintln!("not ready: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match storage.get_pending_migrations().await {
+        Ok(0) => {
+            println!("ready: no pending migrations");
+            ExitCode::SUCCESS
+        }
+        Ok(pending_migrations) => {
+            eprintln!("not ready: {pending_migrations} pending migrations");
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("not ready: {e}");
+            ExitCode::FAILURE
+        }
+    }
 }